@@ -0,0 +1,259 @@
+use bevy::prelude::*;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Editor and prototype friendly descriptions of Rapier collider shapes.
+///
+/// Every shape component rebuilds the entity's [`Collider`] whenever it changes,
+/// so tweaking values in the inspector or in a `.prototype.ron` file is reflected
+/// in the physics world right away.
+pub struct ColliderShapesPlugin;
+
+impl Plugin for ColliderShapesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ColliderShape>()
+            .register_type::<CompoundShape>()
+            .register_type::<Vec<CompoundShape>>()
+            .register_type::<BallCollider>()
+            .register_type::<CapsuleCollider>()
+            .register_type::<CylinderCollider>()
+            .register_type::<ConeCollider>()
+            .register_type::<RoundCubeCollider>()
+            .register_type::<ConvexHullCollider>()
+            .register_type::<CompoundCollider>()
+            .add_systems(
+                Update,
+                (
+                    collider_shape_changed::<BallCollider>,
+                    collider_shape_changed::<CapsuleCollider>,
+                    collider_shape_changed::<CylinderCollider>,
+                    collider_shape_changed::<ConeCollider>,
+                    collider_shape_changed::<RoundCubeCollider>,
+                    collider_shape_changed::<ConvexHullCollider>,
+                    collider_shape_changed::<CompoundCollider>,
+                ),
+            );
+    }
+}
+
+/// A component that knows how to build a Rapier [`Collider`] out of its own data.
+pub trait ColliderShapeSource: Component {
+    fn collider(&self) -> Option<Collider>;
+}
+
+pub fn collider_shape_changed<T: ColliderShapeSource>(
+    mut commands: Commands,
+    shape_query: Query<(Entity, &T), Changed<T>>,
+) {
+    for (entity, shape) in shape_query.iter() {
+        match shape.collider() {
+            Some(collider) => {
+                commands.entity(entity).insert(collider);
+            }
+            None => warn!(
+                "could not build a collider for {:?} from {}",
+                entity,
+                std::any::type_name::<T>()
+            ),
+        }
+    }
+}
+
+/// A single shape description and the one place shape parameters become a [`Collider`],
+/// the shape components build theirs through it and it is the building block of
+/// [`CompoundCollider`].
+#[derive(Reflect, Clone, Debug)]
+pub enum ColliderShape {
+    /// Half extents of the box.
+    Cuboid {
+        size: Vec3,
+    },
+    Ball {
+        radius: f32,
+    },
+    Capsule {
+        start: Vec3,
+        end: Vec3,
+        radius: f32,
+    },
+    Cylinder {
+        half_height: f32,
+        radius: f32,
+    },
+    Cone {
+        half_height: f32,
+        radius: f32,
+    },
+    RoundCuboid {
+        size: Vec3,
+        border_radius: f32,
+    },
+    ConvexHull {
+        points: Vec<Vec3>,
+    },
+}
+
+impl Default for ColliderShape {
+    fn default() -> Self {
+        ColliderShape::Cuboid {
+            size: Vec3::splat(0.5),
+        }
+    }
+}
+
+impl ColliderShape {
+    pub fn collider(&self) -> Option<Collider> {
+        let collider = match self {
+            ColliderShape::Cuboid { size } => Collider::cuboid(size.x, size.y, size.z),
+            ColliderShape::Ball { radius } => Collider::ball(*radius),
+            ColliderShape::Capsule { start, end, radius } => {
+                Collider::capsule(*start, *end, *radius)
+            }
+            ColliderShape::Cylinder {
+                half_height,
+                radius,
+            } => Collider::cylinder(*half_height, *radius),
+            ColliderShape::Cone {
+                half_height,
+                radius,
+            } => Collider::cone(*half_height, *radius),
+            ColliderShape::RoundCuboid {
+                size,
+                border_radius,
+            } => Collider::round_cuboid(size.x, size.y, size.z, *border_radius),
+            ColliderShape::ConvexHull { points } => return Collider::convex_hull(points),
+        };
+        Some(collider)
+    }
+}
+
+#[derive(Component, Schematic, Reflect, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct BallCollider {
+    pub radius: f32,
+}
+
+impl ColliderShapeSource for BallCollider {
+    fn collider(&self) -> Option<Collider> {
+        ColliderShape::Ball {
+            radius: self.radius,
+        }
+        .collider()
+    }
+}
+
+/// Capsule between the two segment end points `start` and `end`.
+#[derive(Component, Schematic, Reflect, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct CapsuleCollider {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub radius: f32,
+}
+
+impl ColliderShapeSource for CapsuleCollider {
+    fn collider(&self) -> Option<Collider> {
+        ColliderShape::Capsule {
+            start: self.start,
+            end: self.end,
+            radius: self.radius,
+        }
+        .collider()
+    }
+}
+
+/// Cylinder aligned with the local Y axis.
+#[derive(Component, Schematic, Reflect, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct CylinderCollider {
+    pub half_height: f32,
+    pub radius: f32,
+}
+
+impl ColliderShapeSource for CylinderCollider {
+    fn collider(&self) -> Option<Collider> {
+        ColliderShape::Cylinder {
+            half_height: self.half_height,
+            radius: self.radius,
+        }
+        .collider()
+    }
+}
+
+/// Cone aligned with the local Y axis, tip pointing up.
+#[derive(Component, Schematic, Reflect, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct ConeCollider {
+    pub half_height: f32,
+    pub radius: f32,
+}
+
+impl ColliderShapeSource for ConeCollider {
+    fn collider(&self) -> Option<Collider> {
+        ColliderShape::Cone {
+            half_height: self.half_height,
+            radius: self.radius,
+        }
+        .collider()
+    }
+}
+
+/// Cuboid with rounded edges; `size` holds the half extents before rounding.
+#[derive(Component, Schematic, Reflect, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct RoundCubeCollider {
+    pub size: Vec3,
+    pub border_radius: f32,
+}
+
+impl ColliderShapeSource for RoundCubeCollider {
+    fn collider(&self) -> Option<Collider> {
+        ColliderShape::RoundCuboid {
+            size: self.size,
+            border_radius: self.border_radius,
+        }
+        .collider()
+    }
+}
+
+#[derive(Component, Schematic, Reflect, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct ConvexHullCollider {
+    pub points: Vec<Vec3>,
+}
+
+impl ColliderShapeSource for ConvexHullCollider {
+    fn collider(&self) -> Option<Collider> {
+        ColliderShape::ConvexHull {
+            points: self.points.clone(),
+        }
+        .collider()
+    }
+}
+
+/// One positioned part of a [`CompoundCollider`].
+#[derive(Reflect, Clone, Debug, Default)]
+pub struct CompoundShape {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub shape: ColliderShape,
+}
+
+#[derive(Component, Schematic, Reflect, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct CompoundCollider {
+    pub shapes: Vec<CompoundShape>,
+}
+
+impl ColliderShapeSource for CompoundCollider {
+    fn collider(&self) -> Option<Collider> {
+        let mut shapes = Vec::with_capacity(self.shapes.len());
+        for part in self.shapes.iter() {
+            shapes.push((part.translation, part.rotation, part.shape.collider()?));
+        }
+        if shapes.is_empty() {
+            return None;
+        }
+        Some(Collider::compound(shapes))
+    }
+}
@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Frames a joint target is looked for before giving up on it.
+const MAX_RESOLVE_ATTEMPTS: u32 = 60;
+
+/// Lets prototypes describe Rapier impulse joints between their entities.
+pub struct JointsPlugin;
+
+impl Plugin for JointsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<JointProto>()
+            .register_type::<JointKind>()
+            .register_type::<JointMotorProto>()
+            .register_type::<Option<Vec2>>()
+            .register_type::<Option<JointMotorProto>>()
+            .add_systems(Update, joint_proto_changed)
+            .add_systems(Update, resolve_joint_targets.after(joint_proto_changed));
+    }
+}
+
+/// An impulse joint between this entity and `target`.
+///
+/// `target` is a `/` separated path of [`Name`]s relative to this entity, where `..`
+/// steps up to the parent, e.g. `"hinge"`, `"../frame"` or `"arm/hand"`.
+/// `local_anchor1` is expressed in the target's frame, `local_anchor2` in this entity's.
+#[derive(Component, Schematic, Reflect, Clone, Debug, Default)]
+#[reflect(Schematic)]
+pub struct JointProto {
+    pub target: String,
+    pub kind: JointKind,
+    #[reflect(default)]
+    pub local_anchor1: Vec3,
+    #[reflect(default)]
+    pub local_anchor2: Vec3,
+}
+
+#[derive(Reflect, Clone, Debug, Default)]
+pub enum JointKind {
+    #[default]
+    Fixed,
+    Revolute {
+        axis: Vec3,
+        limits: Option<Vec2>,
+        motor: Option<JointMotorProto>,
+    },
+    Prismatic {
+        axis: Vec3,
+        limits: Option<Vec2>,
+        motor: Option<JointMotorProto>,
+    },
+    Spherical,
+    Rope {
+        max_length: f32,
+    },
+}
+
+#[derive(Reflect, Clone, Debug, Default)]
+pub struct JointMotorProto {
+    pub target_position: f32,
+    pub target_velocity: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+    #[reflect(default)]
+    pub max_force: Option<f32>,
+}
+
+impl JointProto {
+    pub fn joint(&self) -> GenericJoint {
+        let anchor1 = self.local_anchor1;
+        let anchor2 = self.local_anchor2;
+        match &self.kind {
+            JointKind::Fixed => FixedJointBuilder::new()
+                .local_anchor1(anchor1)
+                .local_anchor2(anchor2)
+                .into(),
+            JointKind::Revolute {
+                axis,
+                limits,
+                motor,
+            } => {
+                let mut builder = RevoluteJointBuilder::new(*axis)
+                    .local_anchor1(anchor1)
+                    .local_anchor2(anchor2);
+                if let Some(limits) = limits {
+                    builder = builder.limits([limits.x, limits.y]);
+                }
+                if let Some(motor) = motor {
+                    builder = builder.motor(
+                        motor.target_position,
+                        motor.target_velocity,
+                        motor.stiffness,
+                        motor.damping,
+                    );
+                    if let Some(max_force) = motor.max_force {
+                        builder = builder.motor_max_force(max_force);
+                    }
+                }
+                builder.into()
+            }
+            JointKind::Prismatic {
+                axis,
+                limits,
+                motor,
+            } => {
+                let mut builder = PrismaticJointBuilder::new(*axis)
+                    .local_anchor1(anchor1)
+                    .local_anchor2(anchor2);
+                if let Some(limits) = limits {
+                    builder = builder.limits([limits.x, limits.y]);
+                }
+                if let Some(motor) = motor {
+                    builder = builder.motor(
+                        motor.target_position,
+                        motor.target_velocity,
+                        motor.stiffness,
+                        motor.damping,
+                    );
+                    if let Some(max_force) = motor.max_force {
+                        builder = builder.motor_max_force(max_force);
+                    }
+                }
+                builder.into()
+            }
+            JointKind::Spherical => SphericalJointBuilder::new()
+                .local_anchor1(anchor1)
+                .local_anchor2(anchor2)
+                .into(),
+            JointKind::Rope { max_length } => RopeJointBuilder::new()
+                .local_anchor1(anchor1)
+                .local_anchor2(anchor2)
+                .limits([0.0, *max_length])
+                .into(),
+        }
+    }
+}
+
+/// Marks a [`JointProto`] that already got its [`ImpulseJoint`], or whose target could
+/// not be found.
+#[derive(Component)]
+pub struct JointResolved;
+
+/// Frames spent looking for the target of a [`JointProto`].
+#[derive(Component, Default)]
+pub struct JointResolveAttempts(u32);
+
+fn joint_proto_changed(
+    mut commands: Commands,
+    changed_query: Query<Entity, (Changed<JointProto>, With<JointResolved>)>,
+) {
+    for entity in changed_query.iter() {
+        commands
+            .entity(entity)
+            .remove::<JointResolved>()
+            .remove::<JointResolveAttempts>()
+            .remove::<ImpulseJoint>();
+    }
+}
+
+/// Joint targets are usually children spawned by the same prototype, so resolving is
+/// retried every frame until the whole hierarchy exists, and given up on after
+/// [`MAX_RESOLVE_ATTEMPTS`] frames.
+fn resolve_joint_targets(
+    mut commands: Commands,
+    mut joint_query: Query<
+        (Entity, &JointProto, Option<&mut JointResolveAttempts>),
+        Without<JointResolved>,
+    >,
+    parent_query: Query<&Parent>,
+    children_query: Query<&Children>,
+    name_query: Query<&Name>,
+) {
+    for (entity, joint_proto, attempts) in joint_query.iter_mut() {
+        let Some(target) = resolve_path(
+            entity,
+            &joint_proto.target,
+            &parent_query,
+            &children_query,
+            &name_query,
+        ) else {
+            let Some(mut attempts) = attempts else {
+                commands
+                    .entity(entity)
+                    .insert(JointResolveAttempts::default());
+                continue;
+            };
+            attempts.0 += 1;
+            if attempts.0 >= MAX_RESOLVE_ATTEMPTS {
+                warn!(
+                    "joint on {:?} could not find its target {:?}, ignoring it",
+                    entity, joint_proto.target
+                );
+                commands
+                    .entity(entity)
+                    .remove::<JointResolveAttempts>()
+                    .insert(JointResolved);
+            }
+            continue;
+        };
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.remove::<JointResolveAttempts>();
+        if target == entity {
+            warn!("joint on {:?} targets itself, ignoring it", entity);
+            entity_commands.insert(JointResolved);
+            continue;
+        }
+        entity_commands.insert((
+            ImpulseJoint::new(target, joint_proto.joint()),
+            JointResolved,
+        ));
+    }
+}
+
+fn resolve_path(
+    from: Entity,
+    path: &str,
+    parent_query: &Query<&Parent>,
+    children_query: &Query<&Children>,
+    name_query: &Query<&Name>,
+) -> Option<Entity> {
+    let mut current = from;
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => current = parent_query.get(current).ok()?.get(),
+            name => {
+                current = *children_query.get(current).ok()?.iter().find(|child| {
+                    name_query
+                        .get(**child)
+                        .map_or(false, |child_name| child_name.as_str() == name)
+                })?;
+            }
+        }
+    }
+    Some(current)
+}
@@ -0,0 +1,201 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, VertexAttributeValues};
+use bevy::scene::SceneInstance;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::parry::transformation::vhacd::VHACDParameters;
+use bevy_rapier3d::prelude::*;
+
+/// Builds the collider of a rigid body from the meshes of its (glTF) scene children.
+pub struct MeshColliderPlugin;
+
+impl Plugin for MeshColliderPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MeshCollider>()
+            .register_type::<MeshColliderShape>()
+            .add_systems(Update, mesh_collider_changed)
+            .add_systems(Update, build_mesh_colliders.after(mesh_collider_changed));
+    }
+}
+
+#[derive(Reflect, Clone, Debug, Default)]
+pub enum MeshColliderShape {
+    /// A single box enclosing all meshes.
+    #[default]
+    Aabb,
+    /// The convex hull of all mesh vertices.
+    ConvexHull,
+    /// V-HACD convex decomposition, good for concave dynamic props.
+    ConvexDecomposition {
+        resolution: u32,
+        max_convex_hulls: u32,
+    },
+    /// Exact triangle mesh, only suited for fixed bodies.
+    TriMesh,
+}
+
+/// Once all scenes below this entity are spawned, computes a [`Collider`] from their
+/// meshes and inserts it on this entity.
+#[derive(Component, Schematic, Reflect, Clone, Debug, Default)]
+#[reflect(Schematic)]
+pub struct MeshCollider {
+    pub shape: MeshColliderShape,
+}
+
+/// Marks a [`MeshCollider`] whose collider has been generated.
+#[derive(Component)]
+pub struct MeshColliderBuilt;
+
+fn mesh_collider_changed(
+    mut commands: Commands,
+    changed_query: Query<Entity, (Changed<MeshCollider>, With<MeshColliderBuilt>)>,
+) {
+    for entity in changed_query.iter() {
+        commands.entity(entity).remove::<MeshColliderBuilt>();
+    }
+}
+
+fn build_mesh_colliders(
+    mut commands: Commands,
+    mesh_collider_query: Query<
+        (Entity, &MeshCollider, Option<&RigidBody>, Option<&Name>),
+        Without<MeshColliderBuilt>,
+    >,
+    children_query: Query<&Children>,
+    scene_instance_query: Query<&SceneInstance>,
+    mesh_query: Query<&Handle<Mesh>>,
+    transform_query: Query<(&Transform, Option<&Parent>)>,
+    scene_spawner: Res<SceneSpawner>,
+    meshes: Res<Assets<Mesh>>,
+) {
+    for (entity, mesh_collider, rigid_body, name) in mesh_collider_query.iter() {
+        let mut has_scene = false;
+        let mut scenes_ready = true;
+        for scene_entity in std::iter::once(entity).chain(children_query.iter_descendants(entity)) {
+            if let Ok(instance) = scene_instance_query.get(scene_entity) {
+                has_scene = true;
+                scenes_ready &= scene_spawner.instance_is_ready(**instance);
+            }
+        }
+        if !has_scene || !scenes_ready {
+            continue;
+        }
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for mesh_entity in children_query.iter_descendants(entity) {
+            let Ok(mesh_handle) = mesh_query.get(mesh_entity) else {
+                continue;
+            };
+            let Some(mesh) = meshes.get(mesh_handle) else {
+                continue;
+            };
+            let to_root = relative_matrix(mesh_entity, entity, &transform_query);
+            append_mesh(mesh, to_root, &mut vertices, &mut indices);
+        }
+        if vertices.is_empty() {
+            // Scene is spawned but its meshes are not loaded yet.
+            continue;
+        }
+
+        if matches!(mesh_collider.shape, MeshColliderShape::TriMesh)
+            && matches!(rigid_body, Some(RigidBody::Dynamic))
+        {
+            warn!(
+                "{:?} ({:?}) uses a trimesh collider on a dynamic body, prefer a convex shape",
+                name, entity
+            );
+        }
+
+        let collider = match &mesh_collider.shape {
+            MeshColliderShape::Aabb => aabb_collider(&vertices),
+            MeshColliderShape::ConvexHull => Collider::convex_hull(&vertices),
+            MeshColliderShape::ConvexDecomposition {
+                resolution,
+                max_convex_hulls,
+            } => {
+                let params = VHACDParameters {
+                    resolution: *resolution,
+                    max_convex_hulls: *max_convex_hulls,
+                    ..default()
+                };
+                Some(Collider::convex_decomposition_with_params(
+                    &vertices, &indices, &params,
+                ))
+            }
+            MeshColliderShape::TriMesh => Some(Collider::trimesh(vertices, indices)),
+        };
+
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(MeshColliderBuilt);
+        match collider {
+            Some(collider) => {
+                entity_commands.insert(collider);
+            }
+            None => warn!(
+                "could not build a mesh collider for {:?} ({:?})",
+                name, entity
+            ),
+        }
+    }
+}
+
+/// Transform of `entity` relative to its ancestor `root`, built from the local transforms
+/// so it does not depend on transform propagation having run for freshly spawned scenes.
+fn relative_matrix(
+    entity: Entity,
+    root: Entity,
+    transform_query: &Query<(&Transform, Option<&Parent>)>,
+) -> Mat4 {
+    let mut matrix = Mat4::IDENTITY;
+    let mut current = entity;
+    while current != root {
+        let Ok((transform, parent)) = transform_query.get(current) else {
+            break;
+        };
+        matrix = transform.compute_matrix() * matrix;
+        match parent {
+            Some(parent) => current = parent.get(),
+            None => break,
+        }
+    }
+    matrix
+}
+
+fn append_mesh(mesh: &Mesh, to_root: Mat4, vertices: &mut Vec<Vec3>, indices: &mut Vec<[u32; 3]>) {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let offset = vertices.len() as u32;
+    vertices.extend(
+        positions
+            .iter()
+            .map(|position| to_root.transform_point3(Vec3::from(*position))),
+    );
+
+    let mesh_indices: Vec<u32> = match mesh.indices() {
+        Some(Indices::U16(values)) => values.iter().map(|i| *i as u32).collect(),
+        Some(Indices::U32(values)) => values.clone(),
+        None => (0..positions.len() as u32).collect(),
+    };
+    indices.extend(mesh_indices.chunks_exact(3).map(|triangle| {
+        [
+            triangle[0] + offset,
+            triangle[1] + offset,
+            triangle[2] + offset,
+        ]
+    }));
+}
+
+fn aabb_collider(vertices: &[Vec3]) -> Option<Collider> {
+    let min = vertices.iter().copied().reduce(Vec3::min)?;
+    let max = vertices.iter().copied().reduce(Vec3::max)?;
+    let half_size = (max - min) * 0.5;
+    let center = (max + min) * 0.5;
+    Some(Collider::compound(vec![(
+        center,
+        Quat::IDENTITY,
+        Collider::cuboid(half_size.x, half_size.y, half_size.z),
+    )]))
+}
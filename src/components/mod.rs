@@ -1,13 +1,34 @@
+pub mod collider_shapes;
+pub mod joints;
+pub mod mesh_collider;
 pub mod rapier_helpers;
+pub mod small_box;
 
 use bevy::prelude::*;
 use bevy_proto::prelude::*;
 
 
+/// Data of a prop that can be fired while held.
+#[derive(Component, Schematic, Reflect, Default, Debug)]
+#[reflect(Schematic)]
+pub struct Weapon {
+    /// Prototype spawned for every shot.
+    pub projectile: String,
+    pub projectile_speed: f32,
+    /// Minimum time between two shots, in seconds.
+    pub fire_interval: f32,
+    /// Where projectiles leave the weapon, in the weapon's local space.
+    pub muzzle_offset: Vec3,
+}
 
-#[derive(Component, Schematic)]
-#[derive(Reflect)]
+/// Gives a prototype entity a [`Name`], e.g. so joints can reference it.
+#[derive(Schematic, Reflect)]
 #[reflect(Schematic)]
-pub struct SmallBox {
+#[schematic(into = Name)]
+pub struct NameProto(pub String);
 
-}
\ No newline at end of file
+impl From<NameProto> for Name {
+    fn from(value: NameProto) -> Self {
+        Name::new(value.0)
+    }
+}
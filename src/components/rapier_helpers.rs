@@ -2,19 +2,30 @@ use bevy::prelude::*;
 use bevy_proto::prelude::*;
 use bevy_rapier3d::prelude::*;
 
+use super::collider_shapes::{collider_shape_changed, ColliderShapeSource};
+
 pub struct RapierHelperPlugin;
 
 impl Plugin for RapierHelperPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<ColliderProto>()
             .register_type::<RigidbodyProto>()
+            .register_type::<RigidbodyParams>()
+            .register_type::<LockedAxesProto>()
+            .register_type::<Option<f32>>()
+            .register_type::<Option<bool>>()
+            .register_type::<Option<i8>>()
+            .register_type::<Option<LockedAxesProto>>()
             .register_type::<CubeCollider>()
             .register_type::<CubeGizmo>()
-            .add_systems(Update, cube_collider_changed)
-            .add_systems(Update, draw_cube_gizmo);
+            .register_type::<VelocityProto>()
+            .add_systems(Update, collider_shape_changed::<CubeCollider>)
+            .add_systems(Update, draw_cube_gizmo)
+            .add_systems(Update, rigidbody_params_changed);
     }
 }
-#[derive(Component, Default, Debug, Reflect)]
+#[derive(Component, Schematic, Default, Debug, Reflect)]
+#[reflect(Schematic)]
 pub struct CubeGizmo {
     pub color: Color,
 }
@@ -31,19 +42,17 @@ pub fn draw_cube_gizmo(
     }
 }
 
-#[derive(Component, Default, Debug, Reflect)]
+/// Cuboid collider described by its half extents.
+#[derive(Component, Schematic, Default, Debug, Reflect)]
+#[reflect(Schematic)]
 pub struct CubeCollider {
     pub size: Vec3,
 }
 
-fn cube_collider_changed(
-    mut commands: Commands,
-    mut collider_query: Query<(Entity, &CubeCollider), Changed<CubeCollider>>,
-) {
-    for (entity, cube_collider) in collider_query.iter_mut() {
-        let size = cube_collider.size;
-        let col = Collider::cuboid(size.x, size.y, size.z);
-        commands.entity(entity).insert(col);
+impl ColliderShapeSource for CubeCollider {
+    fn collider(&self) -> Option<Collider> {
+        let size = self.size;
+        Some(Collider::cuboid(size.x, size.y, size.z))
     }
 }
 
@@ -53,6 +62,8 @@ fn cube_collider_changed(
 pub enum RigidbodyProto {
     Dynamic,
     Fixed,
+    KinematicPositionBased,
+    KinematicVelocityBased,
 }
 
 impl From<RigidbodyProto> for RigidBody {
@@ -60,6 +71,118 @@ impl From<RigidbodyProto> for RigidBody {
         match value {
             RigidbodyProto::Dynamic => RigidBody::Dynamic,
             RigidbodyProto::Fixed => RigidBody::Fixed,
+            RigidbodyProto::KinematicPositionBased => RigidBody::KinematicPositionBased,
+            RigidbodyProto::KinematicVelocityBased => RigidBody::KinematicVelocityBased,
+        }
+    }
+}
+
+/// Common rigid-body and collider parameters.
+///
+/// Every field is optional and can be left out of a prototype; only the
+/// parameters that are set get inserted as their Rapier components.
+/// `mass`, `density`, `friction` and `restitution` apply to the collider on this entity.
+#[derive(Component, Schematic, Reflect, Default, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct RigidbodyParams {
+    #[reflect(default)]
+    pub mass: Option<f32>,
+    #[reflect(default)]
+    pub density: Option<f32>,
+    #[reflect(default)]
+    pub linear_damping: Option<f32>,
+    #[reflect(default)]
+    pub angular_damping: Option<f32>,
+    #[reflect(default)]
+    pub gravity_scale: Option<f32>,
+    #[reflect(default)]
+    pub ccd: Option<bool>,
+    #[reflect(default)]
+    pub locked_axes: Option<LockedAxesProto>,
+    #[reflect(default)]
+    pub friction: Option<f32>,
+    #[reflect(default)]
+    pub restitution: Option<f32>,
+    #[reflect(default)]
+    pub dominance: Option<i8>,
+    #[reflect(default)]
+    pub can_sleep: Option<bool>,
+}
+
+#[derive(Reflect, Default, Clone, Debug)]
+pub struct LockedAxesProto {
+    #[reflect(default)]
+    pub translation_x: bool,
+    #[reflect(default)]
+    pub translation_y: bool,
+    #[reflect(default)]
+    pub translation_z: bool,
+    #[reflect(default)]
+    pub rotation_x: bool,
+    #[reflect(default)]
+    pub rotation_y: bool,
+    #[reflect(default)]
+    pub rotation_z: bool,
+}
+
+impl From<&LockedAxesProto> for LockedAxes {
+    fn from(value: &LockedAxesProto) -> Self {
+        let mut locked_axes = LockedAxes::empty();
+        locked_axes.set(LockedAxes::TRANSLATION_LOCKED_X, value.translation_x);
+        locked_axes.set(LockedAxes::TRANSLATION_LOCKED_Y, value.translation_y);
+        locked_axes.set(LockedAxes::TRANSLATION_LOCKED_Z, value.translation_z);
+        locked_axes.set(LockedAxes::ROTATION_LOCKED_X, value.rotation_x);
+        locked_axes.set(LockedAxes::ROTATION_LOCKED_Y, value.rotation_y);
+        locked_axes.set(LockedAxes::ROTATION_LOCKED_Z, value.rotation_z);
+        locked_axes
+    }
+}
+
+fn rigidbody_params_changed(
+    mut commands: Commands,
+    params_query: Query<(Entity, &RigidbodyParams), Changed<RigidbodyParams>>,
+) {
+    for (entity, params) in params_query.iter() {
+        let mut entity_commands = commands.entity(entity);
+        if let Some(density) = params.density {
+            entity_commands.insert(ColliderMassProperties::Density(density));
+        }
+        // An explicit mass wins over the density.
+        if let Some(mass) = params.mass {
+            entity_commands.insert(ColliderMassProperties::Mass(mass));
+        }
+        if params.linear_damping.is_some() || params.angular_damping.is_some() {
+            entity_commands.insert(Damping {
+                linear_damping: params.linear_damping.unwrap_or(0.0),
+                angular_damping: params.angular_damping.unwrap_or(0.0),
+            });
+        }
+        if let Some(gravity_scale) = params.gravity_scale {
+            entity_commands.insert(GravityScale(gravity_scale));
+        }
+        if let Some(enabled) = params.ccd {
+            entity_commands.insert(Ccd { enabled });
+        }
+        if let Some(locked_axes) = &params.locked_axes {
+            entity_commands.insert(LockedAxes::from(locked_axes));
+        }
+        if let Some(coefficient) = params.friction {
+            entity_commands.insert(Friction::coefficient(coefficient));
+        }
+        if let Some(coefficient) = params.restitution {
+            entity_commands.insert(Restitution::coefficient(coefficient));
+        }
+        if let Some(groups) = params.dominance {
+            entity_commands.insert(Dominance { groups });
+        }
+        match params.can_sleep {
+            Some(false) => {
+                entity_commands.insert(Sleeping::disabled());
+            }
+            Some(true) => {
+                entity_commands.insert(Sleeping::default());
+            }
+            None => {}
         }
     }
 }
@@ -78,3 +201,22 @@ impl From<ColliderProto> for Collider {
         Collider::cuboid(col_state.x, col_state.y, col_state.z)
     }
 }
+
+#[derive(Schematic, Reflect, Default)]
+#[reflect(Schematic)]
+#[schematic(into = Velocity)]
+pub struct VelocityProto {
+    #[reflect(default)]
+    pub linvel: Vec3,
+    #[reflect(default)]
+    pub angvel: Vec3,
+}
+
+impl From<VelocityProto> for Velocity {
+    fn from(value: VelocityProto) -> Self {
+        Velocity {
+            linvel: value.linvel,
+            angvel: value.angvel,
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use bevy::prelude::*;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::damage::{Dead, DeathBehavior, DeathEvent};
+use crate::destruction::Debris;
+use crate::impacts::ImpactEvent;
+use crate::player::player_components::{Grabbable, PIDController};
+
+/// Half extents of `box-small.glb`, whose origin sits at the bottom of the box.
+const MODEL_HALF_SIZE: f32 = 0.25;
+const MODEL_PATH: &str = "models/box-small.glb#Scene0";
+/// Crates smaller than this (half extent) no longer break apart.
+const MIN_BREAKABLE_HALF_SIZE: f32 = 0.06;
+/// A support normal has to point at least this much upwards.
+const MIN_SUPPORT_NORMAL_Y: f32 = 0.7;
+/// How far the center of mass may hang over the support area before the crate tips.
+const STABILITY_MARGIN: f32 = 0.02;
+
+pub struct SmallBoxPlugin;
+
+impl Plugin for SmallBoxPlugin {
+    fn build(&self, app: &mut App) {
+        // Also registered by `ImpactPlugin` and `DamagePlugin`, but the prefab validation
+        // runs without them.
+        app.add_event::<ImpactEvent>()
+            .add_event::<DeathEvent>()
+            .register_type::<SmallBox>()
+            .register_type::<CrateMaterial>()
+            .register_type::<StackState>()
+            .add_systems(Update, small_box_changed)
+            .add_systems(Update, small_box_stacking)
+            .add_systems(Update, small_box_breaking)
+            .add_systems(Update, draw_unstable_small_boxes.after(small_box_stacking));
+    }
+}
+
+/// A crate prop: a box collider of `size` half extents made of `material`,
+/// which breaks into smaller crates when hit harder than `break_impulse`.
+#[derive(Component, Schematic, Reflect, Clone, Debug)]
+#[reflect(Schematic)]
+pub struct SmallBox {
+    pub size: Vec3,
+    pub material: CrateMaterial,
+    /// Impulse in N·s a single impact needs to break the crate.
+    pub break_impulse: f32,
+}
+
+impl Default for SmallBox {
+    fn default() -> Self {
+        Self {
+            size: Vec3::splat(MODEL_HALF_SIZE),
+            material: CrateMaterial::Wood,
+            break_impulse: 8.0,
+        }
+    }
+}
+
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq)]
+pub enum CrateMaterial {
+    Cardboard,
+    #[default]
+    Wood,
+    Metal,
+}
+
+impl CrateMaterial {
+    pub fn density(&self) -> f32 {
+        match self {
+            CrateMaterial::Cardboard => 0.2,
+            CrateMaterial::Wood => 0.7,
+            CrateMaterial::Metal => 4.0,
+        }
+    }
+
+    pub fn friction(&self) -> f32 {
+        match self {
+            CrateMaterial::Cardboard => 0.8,
+            CrateMaterial::Wood => 0.6,
+            CrateMaterial::Metal => 0.4,
+        }
+    }
+
+    pub fn restitution(&self) -> f32 {
+        match self {
+            CrateMaterial::Cardboard => 0.0,
+            CrateMaterial::Wood => 0.1,
+            CrateMaterial::Metal => 0.2,
+        }
+    }
+}
+
+/// How a crate currently rests, updated every frame from its contacts.
+#[derive(Component, Reflect, Default, Debug)]
+pub struct StackState {
+    /// The body carrying most of this crate, if it rests on something.
+    pub supported_by: Option<Entity>,
+    /// Whether the center of mass is above the support area.
+    pub stable: bool,
+    /// Position in the stack counted from the ground, `0` while not resting on anything.
+    pub stack_height: u32,
+}
+
+fn small_box_changed(
+    mut commands: Commands,
+    small_box_query: Query<(Entity, &SmallBox, Option<&Children>), Changed<SmallBox>>,
+    mut model_query: Query<&mut Transform, With<Handle<Scene>>>,
+) {
+    for (entity, small_box, children) in small_box_query.iter() {
+        let size = small_box.size;
+        let material = small_box.material;
+        commands.entity(entity).insert((
+            Collider::cuboid(size.x, size.y, size.z),
+            ColliderMassProperties::Density(material.density()),
+            Friction::coefficient(material.friction()),
+            Restitution::coefficient(material.restitution()),
+            StackState::default(),
+        ));
+
+        let Some(children) = children else {
+            continue;
+        };
+        for child in children.iter() {
+            if let Ok(mut model_transform) = model_query.get_mut(*child) {
+                *model_transform = model_transform_for(size);
+            }
+        }
+    }
+}
+
+fn model_transform_for(size: Vec3) -> Transform {
+    Transform::from_xyz(0.0, -size.y, 0.0).with_scale(size / MODEL_HALF_SIZE)
+}
+
+fn small_box_stacking(
+    rapier_context: Res<RapierContext>,
+    mut small_box_query: Query<(Entity, &GlobalTransform, &mut StackState), With<SmallBox>>,
+) {
+    let heights: Vec<(Entity, u32)> = small_box_query
+        .iter()
+        .map(|(entity, _, state)| (entity, state.stack_height))
+        .collect();
+
+    for (entity, transform, mut state) in small_box_query.iter_mut() {
+        let mut support_points = Vec::new();
+        let mut supported_by = None;
+        let mut strongest_support = 0;
+        for contact_pair in rapier_context.contacts_with(entity) {
+            if !contact_pair.has_any_active_contacts() {
+                continue;
+            }
+            let is_first = contact_pair.collider1() == entity;
+            let other = if is_first {
+                contact_pair.collider2()
+            } else {
+                contact_pair.collider1()
+            };
+            let mut points = 0;
+            for manifold in contact_pair.manifolds() {
+                // The manifold normal points from the first to the second collider.
+                let normal = if is_first {
+                    -manifold.normal()
+                } else {
+                    manifold.normal()
+                };
+                if normal.y < MIN_SUPPORT_NORMAL_Y {
+                    continue;
+                }
+                for contact in manifold.solver_contacts() {
+                    support_points.push(contact.point());
+                    points += 1;
+                }
+            }
+            if points > strongest_support {
+                strongest_support = points;
+                supported_by = Some(rapier_context.collider_parent(other).unwrap_or(other));
+            }
+        }
+
+        let center = transform.translation();
+        state.supported_by = supported_by;
+        state.stable = supported_by.is_some() && is_over_support(center, &support_points);
+        state.stack_height = match supported_by {
+            Some(below) => {
+                heights
+                    .iter()
+                    .find(|(other, _)| *other == below)
+                    .map_or(0, |(_, height)| *height)
+                    + 1
+            }
+            None => 0,
+        };
+    }
+}
+
+/// Checks whether `center` lies above the horizontal bounding rectangle of the support points.
+fn is_over_support(center: Vec3, support_points: &[Vec3]) -> bool {
+    let Some(min) = support_points.iter().copied().reduce(Vec3::min) else {
+        return false;
+    };
+    let Some(max) = support_points.iter().copied().reduce(Vec3::max) else {
+        return false;
+    };
+    center.x >= min.x - STABILITY_MARGIN
+        && center.x <= max.x + STABILITY_MARGIN
+        && center.z >= min.z - STABILITY_MARGIN
+        && center.z <= max.z + STABILITY_MARGIN
+}
+
+fn draw_unstable_small_boxes(
+    small_box_query: Query<(&GlobalTransform, &SmallBox, &StackState)>,
+    mut gizmos: Gizmos,
+) {
+    for (transform, small_box, state) in small_box_query.iter() {
+        if state.supported_by.is_none() || state.stable {
+            continue;
+        }
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        gizmos.cuboid(
+            Transform::from_translation(translation)
+                .with_rotation(rotation)
+                .with_scale(small_box.size * 2.0),
+            Color::RED,
+        );
+    }
+}
+
+/// Kills crates hit hard enough, the destruction module then breaks them like any other
+/// prop dying with [`DeathBehavior::Break`].
+pub fn small_box_breaking(
+    mut commands: Commands,
+    mut impact_events: EventReader<ImpactEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+    small_box_query: Query<&SmallBox, Without<Dead>>,
+) {
+    let mut broken = Vec::new();
+    for impact in impact_events.iter() {
+        for entity in [impact.entity, impact.other] {
+            let Ok(small_box) = small_box_query.get(entity) else {
+                continue;
+            };
+            if impact.impulse >= small_box.break_impulse && !broken.contains(&entity) {
+                broken.push(entity);
+            }
+        }
+    }
+
+    for entity in broken {
+        commands.entity(entity).insert(Dead);
+        death_events.send(DeathEvent {
+            entity,
+            behavior: DeathBehavior::Break,
+        });
+    }
+}
+
+/// Replaces the crate `entity` with eight half-sized crates that keep its motion.
+///
+/// Crates below the minimum size are left alone; returns whether the crate broke.
+pub fn break_small_box(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    entity: Entity,
+    small_box: &SmallBox,
+    transform: &Transform,
+    velocity: Velocity,
+) -> bool {
+    if small_box.size.min_element() < MIN_BREAKABLE_HALF_SIZE {
+        return false;
+    }
+    commands.entity(entity).despawn_recursive();
+
+    let piece_size = small_box.size * 0.5;
+    for index in 0..8 {
+        let octant = Vec3::new(
+            if index & 1 == 0 { -1.0 } else { 1.0 },
+            if index & 2 == 0 { -1.0 } else { 1.0 },
+            if index & 4 == 0 { -1.0 } else { 1.0 },
+        );
+        let offset = transform.rotation * (octant * piece_size);
+        spawn_crate_piece(
+            commands,
+            asset_server,
+            SmallBox {
+                size: piece_size,
+                material: small_box.material,
+                break_impulse: small_box.break_impulse * 0.5,
+            },
+            Transform::from_translation(transform.translation + offset)
+                .with_rotation(transform.rotation),
+            Velocity {
+                linvel: velocity.linvel + velocity.angvel.cross(offset),
+                angvel: velocity.angvel,
+            },
+        );
+    }
+    true
+}
+
+fn spawn_crate_piece(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    small_box: SmallBox,
+    transform: Transform,
+    velocity: Velocity,
+) {
+    let model_transform = model_transform_for(small_box.size);
+    commands
+        .spawn((
+            SpatialBundle {
+                transform,
+                ..default()
+            },
+            RigidBody::Dynamic,
+            Name::new("crate_piece"),
+            small_box,
+            velocity,
+            Grabbable::default(),
+            PIDController::new(0.7, 0.0, 0.3),
+            Debris::default(),
+        ))
+        .with_children(|commands| {
+            commands.spawn(SceneBundle {
+                scene: asset_server.load(MODEL_PATH),
+                transform: model_transform,
+                ..default()
+            });
+        });
+}
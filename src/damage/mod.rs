@@ -0,0 +1,330 @@
+pub mod weapons;
+
+use bevy::prelude::*;
+use bevy_fps_controller::controller::LogicalPlayer;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::impacts::{ImpactEvent, ImpactKind};
+use crate::player::movement_modes::MovementMode;
+
+use self::weapons::WeaponsPlugin;
+
+/// Health, damage sources and what happens when something runs out of health.
+pub struct DamagePlugin;
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .init_resource::<DamageSettings>()
+            .register_type::<DamageSettings>()
+            .register_type::<FallTracker>()
+            .add_plugins(WeaponsPlugin)
+            .add_systems(Update, report_projectile_contacts)
+            .add_systems(
+                Update,
+                (projectile_damage, impact_damage, fall_damage).before(apply_damage),
+            )
+            .add_systems(Update, apply_damage)
+            .add_systems(Update, handle_deaths.after(apply_damage));
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct DamageSettings {
+    /// Damage per joule of kinetic energy released in a collision.
+    pub impact_damage_per_joule: f32,
+    /// Vertical landing speed the player survives without damage.
+    pub safe_fall_speed: f32,
+    /// Damage per m/s of landing speed above `safe_fall_speed`.
+    pub fall_damage_per_speed: f32,
+}
+
+impl Default for DamageSettings {
+    fn default() -> Self {
+        Self {
+            impact_damage_per_joule: 0.5,
+            safe_fall_speed: 10.0,
+            fall_damage_per_speed: 8.0,
+        }
+    }
+}
+
+#[derive(Component, Schematic, Reflect, Debug, Clone)]
+#[reflect(Schematic)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Makes an entity with [`Health`] take damage and decides what happens when it dies.
+#[derive(Component, Schematic, Reflect, Debug, Clone, Default)]
+#[reflect(Schematic)]
+pub struct Damageable {
+    #[reflect(default)]
+    pub resistances: Resistances,
+    pub death: DeathBehavior,
+}
+
+/// Damage multipliers per [`DamageKind`], `1.0` takes full damage and `0.0` is immune.
+#[derive(Reflect, Debug, Clone)]
+pub struct Resistances {
+    pub projectile: f32,
+    pub impact: f32,
+    pub fall: f32,
+    #[reflect(default = "full_damage")]
+    pub explosion: f32,
+}
+
+fn full_damage() -> f32 {
+    1.0
+}
+
+impl Default for Resistances {
+    fn default() -> Self {
+        Self {
+            projectile: 1.0,
+            impact: 1.0,
+            fall: 1.0,
+            explosion: 1.0,
+        }
+    }
+}
+
+impl Resistances {
+    pub fn multiplier(&self, kind: DamageKind) -> f32 {
+        match kind {
+            DamageKind::Projectile => self.projectile,
+            DamageKind::Impact => self.impact,
+            DamageKind::Fall => self.fall,
+            DamageKind::Explosion => self.explosion,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq)]
+pub enum DeathBehavior {
+    #[default]
+    Despawn,
+    /// Break into debris, see `destruction::Fracturable`.
+    Break,
+    /// Return to the spawn point with full health.
+    Respawn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DamageKind {
+    Projectile,
+    Impact,
+    Fall,
+    Explosion,
+}
+
+#[derive(Event, Debug, Clone)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub kind: DamageKind,
+    pub source: Option<Entity>,
+}
+
+#[derive(Event, Debug, Clone)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub behavior: DeathBehavior,
+}
+
+/// Marks an entity whose death has already been reported.
+#[derive(Component)]
+pub struct Dead;
+
+/// Deals a fixed amount of damage to the first damageable thing it hits.
+#[derive(Component, Schematic, Reflect, Debug, Clone, Default)]
+#[reflect(Schematic)]
+pub struct Projectile {
+    pub damage: f32,
+    /// Seconds until the projectile despawns.
+    pub lifetime: f32,
+    #[reflect(ignore)]
+    pub spent: bool,
+}
+
+/// Remembers the vertical speed of the previous frame to detect hard landings.
+#[derive(Component, Reflect, Debug, Default)]
+pub struct FallTracker {
+    pub last_vertical_speed: f32,
+}
+
+/// Projectiles are far too light to reach the default contact force threshold.
+fn report_projectile_contacts(
+    mut commands: Commands,
+    projectile_query: Query<Entity, Added<Projectile>>,
+) {
+    for entity in projectile_query.iter() {
+        commands
+            .entity(entity)
+            .insert(ContactForceEventThreshold(0.0));
+    }
+}
+
+fn projectile_damage(
+    mut commands: Commands,
+    mut impact_events: EventReader<ImpactEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut projectile_query: Query<(Entity, &mut Projectile)>,
+    damageable_query: Query<(), With<Damageable>>,
+    time: Res<Time>,
+) {
+    for impact in impact_events.iter() {
+        for entity in [impact.entity, impact.other] {
+            let Ok((_, mut projectile)) = projectile_query.get_mut(entity) else {
+                continue;
+            };
+            let target = impact.other_than(entity);
+            if projectile.spent || damageable_query.get(target).is_err() {
+                continue;
+            }
+            projectile.spent = true;
+            damage_events.send(DamageEvent {
+                target,
+                amount: projectile.damage,
+                kind: DamageKind::Projectile,
+                source: Some(entity),
+            });
+        }
+    }
+
+    for (entity, mut projectile) in projectile_query.iter_mut() {
+        projectile.lifetime -= time.delta_seconds();
+        if projectile.lifetime <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Objects hitting each other take damage scaled by the kinetic energy of the collision.
+fn impact_damage(
+    mut impact_events: EventReader<ImpactEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    settings: Res<DamageSettings>,
+    rapier_context: Res<RapierContext>,
+    damageable_query: Query<(), With<Damageable>>,
+    body_query: Query<&RapierRigidBodyHandle>,
+    projectile_query: Query<(), With<Projectile>>,
+    fall_tracker_query: Query<(), With<FallTracker>>,
+) {
+    // Dynamic mass of a body, `None` for bodies that do not move from impacts.
+    let dynamic_mass = |entity: Entity| {
+        let handle = body_query.get(entity).ok()?;
+        let body = rapier_context.bodies.get(handle.0)?;
+        body.is_dynamic().then(|| body.mass())
+    };
+
+    for impact in impact_events.iter() {
+        if impact.kind == ImpactKind::Tap {
+            continue;
+        }
+        // Projectiles deal their own, fixed damage.
+        if projectile_query.get(impact.entity).is_ok() || projectile_query.get(impact.other).is_ok()
+        {
+            continue;
+        }
+        let reduced_mass = match (dynamic_mass(impact.entity), dynamic_mass(impact.other)) {
+            (Some(mass1), Some(mass2)) if mass1 + mass2 > 0.0 => mass1 * mass2 / (mass1 + mass2),
+            (Some(mass), None) | (None, Some(mass)) => mass,
+            _ => continue,
+        };
+        let energy = 0.5 * reduced_mass * impact.relative_speed * impact.relative_speed;
+        let amount = energy * settings.impact_damage_per_joule;
+        if amount <= 0.0 {
+            continue;
+        }
+        for target in [impact.entity, impact.other] {
+            let source = impact.other_than(target);
+            if damageable_query.get(target).is_err() {
+                continue;
+            }
+            // Landing on static geometry is covered by `fall_damage`.
+            if fall_tracker_query.get(target).is_ok() && dynamic_mass(source).is_none() {
+                continue;
+            }
+            damage_events.send(DamageEvent {
+                target,
+                amount,
+                kind: DamageKind::Impact,
+                source: Some(source),
+            });
+        }
+    }
+}
+
+/// The player takes damage when landing faster than `safe_fall_speed`.
+fn fall_damage(
+    mut damage_events: EventWriter<DamageEvent>,
+    settings: Res<DamageSettings>,
+    mut player_query: Query<
+        (Entity, &Velocity, &mut FallTracker, Option<&MovementMode>),
+        With<LogicalPlayer>,
+    >,
+) {
+    for (entity, velocity, mut fall_tracker, movement_mode) in player_query.iter_mut() {
+        // Stopping in flight is no landing, walking again starts a fresh fall.
+        if movement_mode.map_or(false, |mode| *mode != MovementMode::Walk) {
+            fall_tracker.last_vertical_speed = 0.0;
+            continue;
+        }
+        let landing_speed = -fall_tracker.last_vertical_speed;
+        let stopped = velocity.linvel.y > fall_tracker.last_vertical_speed * 0.5;
+        if landing_speed > settings.safe_fall_speed && stopped {
+            damage_events.send(DamageEvent {
+                target: entity,
+                amount: (landing_speed - settings.safe_fall_speed) * settings.fall_damage_per_speed,
+                kind: DamageKind::Fall,
+                source: None,
+            });
+        }
+        fall_tracker.last_vertical_speed = velocity.linvel.y;
+    }
+}
+
+pub fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+    mut health_query: Query<(&mut Health, &Damageable), Without<Dead>>,
+) {
+    for damage in damage_events.iter() {
+        let Ok((mut health, damageable)) = health_query.get_mut(damage.target) else {
+            continue;
+        };
+        if health.current <= 0.0 {
+            continue;
+        }
+        health.current -= damage.amount * damageable.resistances.multiplier(damage.kind);
+        if health.current <= 0.0 {
+            commands.entity(damage.target).insert(Dead);
+            death_events.send(DeathEvent {
+                entity: damage.target,
+                behavior: damageable.death,
+            });
+        }
+    }
+}
+
+/// Handles [`DeathBehavior::Despawn`], breaking is done by the destruction module and
+/// respawning by the player module.
+pub fn handle_deaths(mut commands: Commands, mut death_events: EventReader<DeathEvent>) {
+    for death in death_events.iter() {
+        if death.behavior == DeathBehavior::Despawn {
+            commands.entity(death.entity).despawn_recursive();
+        }
+    }
+}
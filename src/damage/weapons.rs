@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::components::Weapon;
+use crate::player::camera_rig::camera_not_orbiting;
+use crate::player::placement::placement_inactive;
+use crate::player::player_components::{Grabber, RightHand};
+use crate::prefabs::spawn_prefab;
+use crate::MainCamera;
+
+const FIRE_BUTTON: MouseButton = MouseButton::Left;
+
+/// Fires held [`Weapon`]s.
+pub struct WeaponsPlugin;
+
+impl Plugin for WeaponsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Trigger>()
+            .add_systems(Update, add_weapon_triggers)
+            .add_systems(
+                Update,
+                fire_held_weapons
+                    .after(add_weapon_triggers)
+                    .run_if(placement_inactive)
+                    .run_if(camera_not_orbiting),
+            );
+    }
+}
+
+/// Firing state of a [`Weapon`].
+#[derive(Component, Reflect, Default, Debug)]
+pub struct Trigger {
+    /// Seconds until the weapon can fire again.
+    pub cooldown: f32,
+    /// The fire button has to be released once after grabbing, otherwise the click that
+    /// grabbed the weapon would immediately fire it.
+    pub armed: bool,
+}
+
+fn add_weapon_triggers(mut commands: Commands, weapon_query: Query<Entity, Added<Weapon>>) {
+    for entity in weapon_query.iter() {
+        commands.entity(entity).insert(Trigger::default());
+    }
+}
+
+fn fire_held_weapons(
+    mut commands: ProtoCommands,
+    mouse: Res<Input<MouseButton>>,
+    time: Res<Time>,
+    grabber_query: Query<&Grabber, With<RightHand>>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+    mut weapon_query: Query<(Entity, &Weapon, &mut Trigger, &GlobalTransform)>,
+) {
+    let held = grabber_query
+        .get_single()
+        .ok()
+        .and_then(|grabber| grabber.grabbed_entity);
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+
+    for (entity, weapon, mut trigger, transform) in weapon_query.iter_mut() {
+        trigger.cooldown = (trigger.cooldown - time.delta_seconds()).max(0.0);
+        if held != Some(entity) {
+            trigger.armed = false;
+            continue;
+        }
+        if !mouse.pressed(FIRE_BUTTON) {
+            trigger.armed = true;
+            continue;
+        }
+        if !trigger.armed || trigger.cooldown > 0.0 {
+            continue;
+        }
+        trigger.cooldown = weapon.fire_interval;
+
+        let muzzle = transform.transform_point(weapon.muzzle_offset);
+        let direction = camera.forward();
+        let projectile = spawn_prefab(
+            &mut commands,
+            &weapon.projectile,
+            Transform::from_translation(muzzle).looking_to(direction, Vec3::Y),
+        );
+        commands
+            .entity(projectile)
+            .entity_commands()
+            .insert(Velocity::linear(direction * weapon.projectile_speed));
+    }
+}
@@ -0,0 +1,23 @@
+pub mod overlay;
+pub mod rewind;
+pub mod snapshots;
+pub mod time_controls;
+
+use bevy::prelude::*;
+
+use self::overlay::OverlayPlugin;
+use self::rewind::RewindPlugin;
+use self::snapshots::SnapshotsPlugin;
+use self::time_controls::TimeControlsPlugin;
+
+/// Tools for inspecting and tuning the simulation while it runs.
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(TimeControlsPlugin)
+            .add_plugins(SnapshotsPlugin)
+            .add_plugins(RewindPlugin)
+            .add_plugins(OverlayPlugin);
+    }
+}
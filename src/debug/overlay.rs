@@ -0,0 +1,256 @@
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::VecDeque;
+
+use crate::player::player_components::{Grabber, PIDController};
+
+const OVERLAY_KEY: KeyCode = KeyCode::F3;
+/// Number of bars in the PID output graph, one per frame.
+const GRAPH_SAMPLES: usize = 120;
+const GRAPH_HEIGHT: f32 = 60.0;
+
+/// On-screen readout of the frame rate and of what the grabbers and their PIDs are doing.
+///
+/// Gains can be changed in the editor's inspector while watching the graph.
+pub struct OverlayPlugin;
+
+impl Plugin for OverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugOverlay>()
+            .add_systems(Startup, setup_overlay)
+            .add_systems(Update, toggle_overlay)
+            .add_systems(
+                Update,
+                (record_pid_output, update_overlay_text, update_pid_graph).after(toggle_overlay),
+            );
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct DebugOverlay {
+    pub visible: bool,
+    /// Magnitude of the tracked PID's output per frame, oldest first.
+    pid_history: VecDeque<f32>,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            pid_history: VecDeque::from(vec![0.0; GRAPH_SAMPLES]),
+        }
+    }
+}
+
+#[derive(Component)]
+struct OverlayRoot;
+
+#[derive(Component)]
+struct OverlayText;
+
+/// One bar of the PID graph, `0` being the oldest sample.
+#[derive(Component)]
+struct GraphBar(usize);
+
+fn setup_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    display: Display::None,
+                    ..default()
+                },
+                ..default()
+            },
+            OverlayRoot,
+            Name::new("debug_overlay"),
+        ))
+        .with_children(|commands| {
+            commands.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                OverlayText,
+            ));
+            commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        height: Val::Px(GRAPH_HEIGHT),
+                        align_items: AlignItems::FlexEnd,
+                        margin: UiRect::top(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                    ..default()
+                })
+                .with_children(|commands| {
+                    for index in 0..GRAPH_SAMPLES {
+                        commands.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Px(2.0),
+                                    height: Val::Px(0.0),
+                                    ..default()
+                                },
+                                background_color: Color::GREEN.into(),
+                                ..default()
+                            },
+                            GraphBar(index),
+                        ));
+                    }
+                });
+        });
+}
+
+fn toggle_overlay(
+    key: Res<Input<KeyCode>>,
+    mut overlay: ResMut<DebugOverlay>,
+    mut root_query: Query<&mut Style, With<OverlayRoot>>,
+) {
+    if !key.just_pressed(OVERLAY_KEY) {
+        return;
+    }
+    overlay.visible = !overlay.visible;
+    for mut style in root_query.iter_mut() {
+        style.display = if overlay.visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// Object a grabber is currently working on, held or still being attracted.
+fn grabber_target(grabber: &Grabber) -> Option<Entity> {
+    grabber.grabbed_entity.or(grabber.attracted_target)
+}
+
+/// Samples the PID output of the first grabber that is attracting something.
+///
+/// The PID only runs while attracting, a held object samples `0` instead of its last output.
+fn record_pid_output(
+    mut overlay: ResMut<DebugOverlay>,
+    grabber_query: Query<&Grabber>,
+    pid_query: Query<&PIDController>,
+) {
+    let output = grabber_query
+        .iter()
+        .filter(|grabber| grabber.grabbed_entity.is_none())
+        .filter_map(|grabber| grabber.attracted_target)
+        .find_map(|target| pid_query.get(target).ok())
+        .map_or(0.0, |pid| pid.output().length());
+    overlay.pid_history.pop_front();
+    overlay.pid_history.push_back(output);
+}
+
+fn update_overlay_text(
+    overlay: Res<DebugOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+    grabber_query: Query<(Entity, &Grabber, &GlobalTransform)>,
+    target_query: Query<(&Transform, Option<&Velocity>, Option<&PIDController>)>,
+    name_query: Query<&Name>,
+    mut text_query: Query<&mut Text, With<OverlayText>>,
+) {
+    if !overlay.visible {
+        return;
+    }
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let name = |entity: Option<Entity>| match entity {
+        None => "-".to_string(),
+        Some(entity) => name_query
+            .get(entity)
+            .map_or_else(|_| format!("{:?}", entity), |name| name.to_string()),
+    };
+
+    let fps = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or_default();
+    let frame_time = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|frame_time| frame_time.smoothed())
+        .unwrap_or_default();
+    let mut lines = vec![format!("fps {:.0}  frame {:.1} ms", fps, frame_time)];
+
+    for (entity, grabber, grabber_transform) in grabber_query.iter() {
+        lines.push(String::new());
+        lines.push(name(Some(entity)));
+        lines.push(format!(
+            "  potential {}  attracted {}  grabbed {}",
+            name(grabber.potential_target),
+            name(grabber.attracted_target),
+            name(grabber.grabbed_entity)
+        ));
+        let Some((transform, velocity, pid)) =
+            grabber_target(grabber).and_then(|target| target_query.get(target).ok())
+        else {
+            continue;
+        };
+        let speed = velocity.map_or(0.0, |velocity| velocity.linvel.length());
+        lines.push(format!(
+            "  distance {:.2} m  speed {:.2} m/s",
+            grabber_transform
+                .translation()
+                .distance(transform.translation),
+            speed
+        ));
+        if let Some(pid) = pid {
+            lines.push(format!(
+                "  pid p {:.2} i {:.2} d {:.2}",
+                pid.p_factor, pid.i_factor, pid.d_factor
+            ));
+            if grabber.grabbed_entity.is_some() {
+                lines.push("  idle while held, the joint holds the object".to_string());
+                continue;
+            }
+            lines.push(format!("  error {}", format_vec(pid.error())));
+            lines.push(format!("  integral {}", format_vec(pid.integral())));
+            lines.push(format!("  derivative {}", format_vec(pid.derivative())));
+            lines.push(format!("  output {}", format_vec(pid.output())));
+        }
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "pid output, peak {:.3}",
+        overlay.pid_history.iter().copied().fold(0.0, f32::max)
+    ));
+    text.sections[0].value = lines.join("\n");
+}
+
+fn format_vec(value: Vec3) -> String {
+    format!(
+        "{:.3} ({:.3}, {:.3}, {:.3})",
+        value.length(),
+        value.x,
+        value.y,
+        value.z
+    )
+}
+
+/// Scales the graph to the largest output currently in the history.
+fn update_pid_graph(overlay: Res<DebugOverlay>, mut bar_query: Query<(&mut Style, &GraphBar)>) {
+    if !overlay.visible {
+        return;
+    }
+    let max = overlay
+        .pid_history
+        .iter()
+        .copied()
+        .fold(f32::EPSILON, f32::max);
+    for (mut style, bar) in bar_query.iter_mut() {
+        let value = overlay.pid_history.get(bar.0).copied().unwrap_or_default();
+        style.height = Val::Px(value / max * GRAPH_HEIGHT);
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::VecDeque;
+
+use super::snapshots::{Snapshots, WorldSnapshot};
+use super::time_controls::{
+    apply_time_controls, physics_step_seconds, time_control_input, TimeControls,
+};
+
+const REWIND_KEY: KeyCode = KeyCode::Back;
+const SCRUB_BACK_KEY: KeyCode = KeyCode::Left;
+const SCRUB_FORWARD_KEY: KeyCode = KeyCode::Right;
+/// Held with the scrub keys to move a second at a time.
+const SCRUB_FAST_KEY: KeyCode = KeyCode::ShiftLeft;
+
+/// Records the last seconds of the simulation and lets it be scrubbed back and resumed.
+pub struct RewindPlugin;
+
+impl Plugin for RewindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RewindSettings>()
+            .init_resource::<RewindBuffer>()
+            .register_type::<RewindSettings>()
+            .add_systems(Startup, setup_rewind_text)
+            .add_systems(PostUpdate, record_snapshots.after(PhysicsSet::Writeback))
+            .add_systems(
+                Update,
+                toggle_rewind
+                    .after(time_control_input)
+                    .before(apply_time_controls),
+            )
+            .add_systems(Update, scrub_rewind.after(toggle_rewind))
+            .add_systems(Update, update_rewind_text.after(scrub_rewind));
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct RewindSettings {
+    /// How many seconds of history are kept.
+    pub seconds: f32,
+}
+
+impl Default for RewindSettings {
+    fn default() -> Self {
+        Self { seconds: 10.0 }
+    }
+}
+
+/// The state after every physics step, oldest first.
+#[derive(Resource, Default, Debug)]
+pub struct RewindBuffer {
+    snapshots: VecDeque<RecordedStep>,
+    /// Index of the restored snapshot while rewinding.
+    cursor: Option<usize>,
+}
+
+impl RewindBuffer {
+    pub fn is_rewinding(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.cursor = None;
+    }
+
+    /// Simulated seconds covered by the steps after `index`.
+    fn seconds_after(&self, index: usize) -> f32 {
+        match (self.snapshots.back(), self.snapshots.get(index)) {
+            (Some(newest), Some(step)) => newest.elapsed - step.elapsed,
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RecordedStep {
+    snapshot: WorldSnapshot,
+    /// Simulated seconds since recording started, at the end of the step.
+    elapsed: f32,
+}
+
+#[derive(Component)]
+struct RewindText;
+
+/// Records the outcome of every physics step, which follows Rapier's own timestep.
+fn record_snapshots(
+    settings: Res<RewindSettings>,
+    time: Res<Time>,
+    rapier_config: Res<RapierConfiguration>,
+    mut buffer: ResMut<RewindBuffer>,
+    snapshots: Snapshots,
+) {
+    let seconds = physics_step_seconds(&rapier_config, &time);
+    if buffer.is_rewinding() || seconds <= 0.0 {
+        return;
+    }
+    let elapsed = buffer.snapshots.back().map_or(0.0, |step| step.elapsed) + seconds;
+    buffer.snapshots.push_back(RecordedStep {
+        snapshot: snapshots.capture(),
+        elapsed,
+    });
+    while buffer.snapshots.len() > 1 && buffer.seconds_after(0) > settings.seconds {
+        buffer.snapshots.pop_front();
+    }
+}
+
+/// Enters rewind mode on the latest snapshot, leaving it resumes from the restored one.
+///
+/// Unpausing the time controls while rewinding resumes as well.
+fn toggle_rewind(
+    key: Res<Input<KeyCode>>,
+    mut buffer: ResMut<RewindBuffer>,
+    mut time_controls: ResMut<TimeControls>,
+) {
+    match buffer.cursor {
+        None => {
+            if !key.just_pressed(REWIND_KEY) || buffer.snapshots.is_empty() {
+                return;
+            }
+            buffer.cursor = Some(buffer.snapshots.len() - 1);
+            time_controls.paused = true;
+        }
+        Some(cursor) => {
+            if !key.just_pressed(REWIND_KEY) && time_controls.paused {
+                return;
+            }
+            // The abandoned future is dropped, recording continues from the restored tick.
+            buffer.snapshots.truncate(cursor + 1);
+            buffer.cursor = None;
+            time_controls.paused = false;
+        }
+    }
+}
+
+fn scrub_rewind(
+    key: Res<Input<KeyCode>>,
+    mut buffer: ResMut<RewindBuffer>,
+    mut snapshots: Snapshots,
+) {
+    let Some(cursor) = buffer.cursor else {
+        return;
+    };
+    let last = buffer.snapshots.len().saturating_sub(1);
+    // Without the fast key a single step, with it about a second of steps.
+    let fast = key.pressed(SCRUB_FAST_KEY);
+    let mut new_cursor = cursor;
+    let elapsed = |index: usize| buffer.snapshots[index].elapsed;
+    if key.pressed(SCRUB_BACK_KEY) {
+        let start = new_cursor;
+        while new_cursor > 0
+            && (new_cursor == start || (fast && elapsed(start) - elapsed(new_cursor) < 1.0))
+        {
+            new_cursor -= 1;
+        }
+    }
+    if key.pressed(SCRUB_FORWARD_KEY) {
+        let start = new_cursor;
+        while new_cursor < last
+            && (new_cursor == start || (fast && elapsed(new_cursor) - elapsed(start) < 1.0))
+        {
+            new_cursor += 1;
+        }
+    }
+    // Restore when rewinding starts and whenever the cursor moves.
+    if new_cursor == cursor && !buffer.is_changed() {
+        return;
+    }
+    if new_cursor != cursor {
+        buffer.cursor = Some(new_cursor);
+    }
+    if let Some(step) = buffer.snapshots.get(new_cursor) {
+        snapshots.restore(&step.snapshot);
+    }
+}
+
+fn setup_rewind_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 18.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        RewindText,
+        Name::new("rewind_text"),
+    ));
+}
+
+fn update_rewind_text(
+    buffer: Res<RewindBuffer>,
+    mut text_query: Query<&mut Text, With<RewindText>>,
+) {
+    if !buffer.is_changed() {
+        return;
+    }
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    text.sections[0].value = match buffer.cursor {
+        None => String::new(),
+        Some(cursor) => {
+            format!(
+                "rewind -{:.2}s ({}/{})\n[Left/Right] scrub  [Shift] faster  [Backspace] resume",
+                buffer.seconds_after(cursor),
+                cursor + 1,
+                buffer.snapshots.len()
+            )
+        }
+    };
+}
@@ -0,0 +1,166 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::rewind::RewindBuffer;
+use crate::player::hold_controls::{hold_frame, RotationSnap};
+use crate::player::player_components::Grabber;
+
+const QUICKSAVE_KEY: KeyCode = KeyCode::F5;
+const QUICKLOAD_KEY: KeyCode = KeyCode::F9;
+
+/// Quicksaving and quickloading the physical state of the scene.
+pub struct SnapshotsPlugin;
+
+impl Plugin for SnapshotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<QuickSave>()
+            .add_systems(Update, (quicksave, quickload));
+    }
+}
+
+/// Physical state of every dynamic body and the grab state of every [`Grabber`] at one moment.
+///
+/// Entities are stored by id, bodies that were despawned in the meantime are skipped when
+/// restoring and bodies spawned since are left alone.
+#[derive(Debug, Clone, Default)]
+pub struct WorldSnapshot {
+    pub bodies: Vec<BodySnapshot>,
+    pub grabbers: Vec<GrabberSnapshot>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BodySnapshot {
+    pub entity: Entity,
+    pub transform: Transform,
+    pub velocity: Velocity,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GrabberSnapshot {
+    pub entity: Entity,
+    pub attracted_target: Option<Entity>,
+    pub grabbed_entity: Option<Entity>,
+    pub hold_offset: f32,
+    pub hold_rotation: Quat,
+}
+
+/// The scene state saved with the quicksave key.
+#[derive(Resource, Default, Debug)]
+pub struct QuickSave(pub Option<WorldSnapshot>);
+
+/// Access to everything a [`WorldSnapshot`] covers.
+#[derive(SystemParam)]
+pub struct Snapshots<'w, 's> {
+    commands: Commands<'w, 's>,
+    rotation_snap: Res<'w, RotationSnap>,
+    body_query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static RigidBody,
+            &'static mut Transform,
+            &'static mut Velocity,
+        ),
+    >,
+    grabber_query: Query<'w, 's, (Entity, &'static mut Grabber)>,
+}
+
+impl<'w, 's> Snapshots<'w, 's> {
+    pub fn capture(&self) -> WorldSnapshot {
+        let bodies = self
+            .body_query
+            .iter()
+            .filter(|(_, rigid_body, ..)| **rigid_body == RigidBody::Dynamic)
+            .map(|(entity, _, transform, velocity)| BodySnapshot {
+                entity,
+                transform: *transform,
+                velocity: *velocity,
+            })
+            .collect();
+        let grabbers = self
+            .grabber_query
+            .iter()
+            .map(|(entity, grabber)| GrabberSnapshot {
+                entity,
+                attracted_target: grabber.attracted_target,
+                grabbed_entity: grabber.grabbed_entity,
+                hold_offset: grabber.hold_offset,
+                hold_rotation: grabber.hold_rotation,
+            })
+            .collect();
+        WorldSnapshot { bodies, grabbers }
+    }
+
+    /// Puts the bodies back where they were and re-attaches what was held back then.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        for body in &snapshot.bodies {
+            let Ok((_, _, mut transform, mut velocity)) = self.body_query.get_mut(body.entity)
+            else {
+                continue;
+            };
+            *transform = body.transform;
+            // Rapier wakes bodies up when their velocity is set.
+            *velocity = body.velocity;
+        }
+
+        for saved in &snapshot.grabbers {
+            let Ok((entity, mut grabber)) = self.grabber_query.get_mut(saved.entity) else {
+                continue;
+            };
+            grabber.attracted_target = saved.attracted_target;
+            grabber.hold_offset = saved.hold_offset;
+            grabber.hold_rotation = saved.hold_rotation;
+
+            let held = saved
+                .grabbed_entity
+                .filter(|held| self.body_query.contains(*held));
+            if grabber.grabbed_entity == held {
+                continue;
+            }
+            grabber.grabbed_entity = held;
+            match held {
+                Some(held) => {
+                    let (anchor, basis) = hold_frame(&grabber, *self.rotation_snap);
+                    let joint = FixedJointBuilder::new()
+                        .local_anchor1(anchor)
+                        .local_basis1(basis);
+                    self.commands
+                        .entity(entity)
+                        .insert(ImpulseJoint::new(held, joint));
+                }
+                None => {
+                    self.commands.entity(entity).remove::<ImpulseJoint>();
+                }
+            }
+        }
+    }
+}
+
+fn quicksave(key: Res<Input<KeyCode>>, mut quick_save: ResMut<QuickSave>, snapshots: Snapshots) {
+    if !key.just_pressed(QUICKSAVE_KEY) {
+        return;
+    }
+    quick_save.0 = Some(snapshots.capture());
+    info!("quicksaved");
+}
+
+fn quickload(
+    key: Res<Input<KeyCode>>,
+    quick_save: Res<QuickSave>,
+    mut rewind_buffer: ResMut<RewindBuffer>,
+    mut snapshots: Snapshots,
+) {
+    if !key.just_pressed(QUICKLOAD_KEY) {
+        return;
+    }
+    let Some(snapshot) = &quick_save.0 else {
+        info!("nothing quicksaved yet");
+        return;
+    };
+    snapshots.restore(snapshot);
+    // The recorded history does not lead up to the loaded state anymore.
+    rewind_buffer.clear();
+    info!("quickloaded");
+}
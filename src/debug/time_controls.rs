@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+const PAUSE_KEY: KeyCode = KeyCode::F6;
+const STEP_KEY: KeyCode = KeyCode::F7;
+const SLOWER_KEY: KeyCode = KeyCode::BracketLeft;
+const FASTER_KEY: KeyCode = KeyCode::BracketRight;
+/// Length of one physics tick, also the largest step Rapier takes at normal speed.
+pub const TICK: f32 = 1.0 / 60.0;
+const TIME_SCALES: [f32; 7] = [0.1, 0.25, 0.5, 0.75, 1.0, 1.5, 2.0];
+
+/// Pausing, single-stepping and slowing down the simulation.
+///
+/// The scale is applied to [`Time`] itself, so every system using its delta slows down
+/// together with the physics.
+pub struct TimeControlsPlugin;
+
+impl Plugin for TimeControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeControls>()
+            .register_type::<TimeControls>()
+            .add_systems(Startup, setup_time_text)
+            .add_systems(Update, (time_control_input, apply_time_controls).chain())
+            .add_systems(Update, update_time_text.after(apply_time_controls));
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct TimeControls {
+    pub paused: bool,
+    /// Speed of the simulation relative to real time, from `0.1` to `2`.
+    pub time_scale: f32,
+    /// A single tick was requested while paused.
+    pub step_requested: bool,
+}
+
+impl Default for TimeControls {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            time_scale: 1.0,
+            step_requested: false,
+        }
+    }
+}
+
+#[derive(Component)]
+struct TimeText;
+
+pub fn time_control_input(key: Res<Input<KeyCode>>, mut controls: ResMut<TimeControls>) {
+    if key.just_pressed(PAUSE_KEY) {
+        controls.paused = !controls.paused;
+    }
+    if key.just_pressed(STEP_KEY) {
+        // Stepping always happens from a paused simulation.
+        controls.paused = true;
+        controls.step_requested = true;
+    }
+    if key.just_pressed(SLOWER_KEY) {
+        controls.time_scale = TIME_SCALES
+            .into_iter()
+            .rev()
+            .find(|scale| *scale < controls.time_scale - f32::EPSILON)
+            .unwrap_or(TIME_SCALES[0]);
+    }
+    if key.just_pressed(FASTER_KEY) {
+        controls.time_scale = TIME_SCALES
+            .into_iter()
+            .find(|scale| *scale > controls.time_scale + f32::EPSILON)
+            .unwrap_or(TIME_SCALES[TIME_SCALES.len() - 1]);
+    }
+}
+
+/// Drives [`Time`] and the Rapier pipeline from the [`TimeControls`].
+///
+/// A step keeps [`Time`] paused and lets Rapier take exactly one tick of [`TICK`] seconds,
+/// independent of how long the frame took.
+pub fn apply_time_controls(
+    mut controls: ResMut<TimeControls>,
+    mut time: ResMut<Time>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    let stepping = controls.paused && controls.step_requested;
+    if stepping {
+        controls.step_requested = false;
+    }
+
+    if controls.paused {
+        time.pause();
+    } else {
+        time.unpause();
+        time.set_relative_speed(controls.time_scale);
+    }
+
+    rapier_config.physics_pipeline_active = !controls.paused || stepping;
+    rapier_config.timestep_mode = if stepping {
+        TimestepMode::Fixed {
+            dt: TICK,
+            substeps: 1,
+        }
+    } else {
+        // Rapier takes its steps from the scaled delta, fast motion gets more substeps
+        // so a single step never gets longer than a tick.
+        TimestepMode::Variable {
+            max_dt: TICK * controls.time_scale.max(1.0),
+            time_scale: 1.0,
+            substeps: controls.time_scale.ceil().max(1.0) as usize,
+        }
+    };
+}
+
+/// Seconds Rapier simulates in this frame's step.
+pub fn physics_step_seconds(rapier_config: &RapierConfiguration, time: &Time) -> f32 {
+    if !rapier_config.physics_pipeline_active {
+        return 0.0;
+    }
+    match rapier_config.timestep_mode {
+        TimestepMode::Fixed { dt, substeps } => dt * substeps as f32,
+        TimestepMode::Variable {
+            max_dt, time_scale, ..
+        } => (time.delta_seconds() * time_scale).min(max_dt),
+        TimestepMode::Interpolated { .. } => time.delta_seconds(),
+    }
+}
+
+fn setup_time_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 18.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        TimeText,
+        Name::new("time_text"),
+    ));
+}
+
+fn update_time_text(controls: Res<TimeControls>, mut text_query: Query<&mut Text, With<TimeText>>) {
+    if !controls.is_changed() {
+        return;
+    }
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    text.sections[0].value = if controls.paused {
+        "paused\n[F6] resume  [F7] step".to_string()
+    } else if controls.time_scale != 1.0 {
+        format!("time {:.2}x\n[[/]] speed  [F6] pause", controls.time_scale)
+    } else {
+        String::new()
+    };
+}
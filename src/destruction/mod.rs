@@ -0,0 +1,295 @@
+use bevy::prelude::*;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::components::small_box::{break_small_box, small_box_breaking, SmallBox};
+use crate::damage::{DeathBehavior, DeathEvent};
+use crate::player::player_components::{Grabbable, Grabber, PIDController};
+use crate::prefabs::spawn_prefab;
+
+/// Replaces destroyed props with fragments and keeps the amount of debris in check.
+pub struct DestructionPlugin;
+
+impl Plugin for DestructionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebrisSettings>()
+            .register_type::<DebrisSettings>()
+            .register_type::<Debris>()
+            .add_systems(Update, break_dead_props.after(small_box_breaking))
+            .add_systems(Update, clean_up_debris);
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct DebrisSettings {
+    /// Seconds debris stays around unless it has its own lifetime.
+    pub lifetime: f32,
+    /// Maximum number of debris bodies, the oldest ones are removed first.
+    pub budget: usize,
+}
+
+impl Default for DebrisSettings {
+    fn default() -> Self {
+        Self {
+            lifetime: 20.0,
+            budget: 64,
+        }
+    }
+}
+
+/// Lets a prop with [`DeathBehavior::Break`] fall apart into fragments.
+#[derive(Component, Schematic, Reflect, Debug, Clone, Default)]
+#[reflect(Schematic)]
+pub struct Fracturable {
+    pub pattern: FracturePattern,
+    /// Lifetime of the fragments, `None` uses [`DebrisSettings::lifetime`].
+    #[reflect(default)]
+    pub debris_lifetime: Option<f32>,
+}
+
+#[derive(Reflect, Debug, Clone)]
+pub enum FracturePattern {
+    /// Slices the bounding box of the collider into a grid of `cells` box fragments.
+    Grid { cells: UVec3, color: Color },
+    /// Pre-authored fragment prototypes, placed relative to the prop.
+    Prefabs(Vec<FragmentProto>),
+}
+
+impl Default for FracturePattern {
+    fn default() -> Self {
+        FracturePattern::Grid {
+            cells: UVec3::splat(2),
+            color: Color::GRAY,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Default)]
+pub struct FragmentProto {
+    pub prototype: String,
+    #[reflect(default)]
+    pub translation: Vec3,
+    #[reflect(default)]
+    pub rotation: Quat,
+}
+
+/// A fragment of a destroyed prop, removed after its lifetime or when over budget.
+#[derive(Component, Reflect, Debug, Default)]
+pub struct Debris {
+    pub age: f32,
+    /// `None` uses [`DebrisSettings::lifetime`].
+    pub lifetime: Option<f32>,
+}
+
+impl Debris {
+    pub fn with_lifetime(lifetime: Option<f32>) -> Self {
+        Self { age: 0.0, lifetime }
+    }
+}
+
+fn break_dead_props(
+    mut commands: Commands,
+    mut proto_commands: ProtoCommands,
+    mut death_events: EventReader<DeathEvent>,
+    fracturable_query: Query<(
+        &Fracturable,
+        &Transform,
+        Option<&Velocity>,
+        Option<&Collider>,
+        Option<&ColliderMassProperties>,
+    )>,
+    small_box_query: Query<(&SmallBox, &Transform, Option<&Velocity>)>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut broken = Vec::new();
+    for death in death_events.iter() {
+        // A crate can be reported by its impact and by its damage in the same frame.
+        if death.behavior != DeathBehavior::Break || broken.contains(&death.entity) {
+            continue;
+        }
+        let entity = death.entity;
+        broken.push(entity);
+
+        if let Ok((fracturable, transform, velocity, collider, mass_properties)) =
+            fracturable_query.get(entity)
+        {
+            let velocity = velocity.copied().unwrap_or_default();
+            let lifetime = fracturable.debris_lifetime;
+            match &fracturable.pattern {
+                FracturePattern::Grid { cells, color } => {
+                    let Some(collider) = collider else {
+                        warn!("{:?} can not be sliced without a collider", entity);
+                        commands.entity(entity).despawn_recursive();
+                        continue;
+                    };
+                    let mesh = meshes.add(shape::Cube { size: 1.0 }.into());
+                    let material = materials.add((*color).into());
+                    let cells = grid_cells(collider, *cells);
+                    let mass = fragment_mass(mass_properties, 1.0 / cells.len() as f32);
+                    for (offset, half_extents) in cells {
+                        commands
+                            .spawn((
+                                SpatialBundle::from_transform(fragment_transform(
+                                    transform,
+                                    offset,
+                                    Quat::IDENTITY,
+                                )),
+                                RigidBody::Dynamic,
+                                Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+                                mass,
+                                Name::new("fragment"),
+                                fragment_bundle(transform, velocity, offset, lifetime),
+                            ))
+                            .with_children(|commands| {
+                                commands.spawn(PbrBundle {
+                                    mesh: mesh.clone(),
+                                    material: material.clone(),
+                                    transform: Transform::from_scale(half_extents * 2.0),
+                                    ..default()
+                                });
+                            });
+                    }
+                }
+                FracturePattern::Prefabs(fragments) => {
+                    for fragment in fragments {
+                        let fragment_entity = spawn_prefab(
+                            &mut proto_commands,
+                            &fragment.prototype,
+                            fragment_transform(transform, fragment.translation, fragment.rotation),
+                        );
+                        // Inserted through the same queue so it overrides the prototype's values.
+                        proto_commands
+                            .entity(fragment_entity)
+                            .entity_commands()
+                            .insert(fragment_bundle(
+                                transform,
+                                velocity,
+                                fragment.translation,
+                                lifetime,
+                            ));
+                    }
+                }
+            }
+            commands.entity(entity).despawn_recursive();
+        } else if let Ok((small_box, transform, velocity)) = small_box_query.get(entity) {
+            let broke = break_small_box(
+                &mut commands,
+                &asset_server,
+                entity,
+                small_box,
+                transform,
+                velocity.copied().unwrap_or_default(),
+            );
+            if !broke {
+                commands.entity(entity).despawn_recursive();
+            }
+        } else {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// World transform of a fragment placed at `translation`/`rotation` relative to the prop.
+fn fragment_transform(prop: &Transform, translation: Vec3, rotation: Quat) -> Transform {
+    Transform::from_translation(prop.transform_point(translation))
+        .with_rotation(prop.rotation * rotation)
+}
+
+/// Physical state shared by all fragments: the prop's motion at the fragment's
+/// position, plus what makes it grabbable debris.
+fn fragment_bundle(
+    prop: &Transform,
+    velocity: Velocity,
+    translation: Vec3,
+    lifetime: Option<f32>,
+) -> impl Bundle {
+    let offset = prop.rotation * translation;
+    (
+        Velocity {
+            linvel: velocity.linvel + velocity.angvel.cross(offset),
+            angvel: velocity.angvel,
+        },
+        Grabbable::default(),
+        PIDController::new(0.7, 0.0, 0.3),
+        Debris::with_lifetime(lifetime),
+    )
+}
+
+/// Mass of a fragment making up `fraction` of the prop's volume.
+///
+/// A density carries over as is, a total mass is split so the fragments weigh what the
+/// prop did.
+fn fragment_mass(
+    mass_properties: Option<&ColliderMassProperties>,
+    fraction: f32,
+) -> ColliderMassProperties {
+    match mass_properties {
+        Some(ColliderMassProperties::Density(density)) => ColliderMassProperties::Density(*density),
+        Some(ColliderMassProperties::Mass(mass)) => ColliderMassProperties::Mass(mass * fraction),
+        // The prop's center of mass and inertia don't apply to a fragment.
+        Some(ColliderMassProperties::MassProperties(properties)) => {
+            ColliderMassProperties::Mass(properties.mass * fraction)
+        }
+        None => ColliderMassProperties::default(),
+    }
+}
+
+/// Center offsets and half extents of `cells` equal boxes filling the collider's bounding box.
+fn grid_cells(collider: &Collider, cells: UVec3) -> Vec<(Vec3, Vec3)> {
+    let aabb = collider.raw.compute_local_aabb();
+    let min: Vec3 = aabb.mins.into();
+    let max: Vec3 = aabb.maxs.into();
+    let cells = cells.max(UVec3::ONE);
+    let cell_size = (max - min) / cells.as_vec3();
+
+    let mut result = Vec::new();
+    for x in 0..cells.x {
+        for y in 0..cells.y {
+            for z in 0..cells.z {
+                let index = UVec3::new(x, y, z).as_vec3();
+                let center = min + (index + 0.5) * cell_size;
+                result.push((center, cell_size * 0.5));
+            }
+        }
+    }
+    result
+}
+
+/// Ages debris, removes it once its lifetime is over and keeps it within the budget.
+///
+/// Debris that is currently held is left alone.
+fn clean_up_debris(
+    mut commands: Commands,
+    settings: Res<DebrisSettings>,
+    time: Res<Time>,
+    mut debris_query: Query<(Entity, &mut Debris)>,
+    grabber_query: Query<&Grabber>,
+) {
+    let held: Vec<Entity> = grabber_query
+        .iter()
+        .filter_map(|grabber| grabber.grabbed_entity.or(grabber.attracted_target))
+        .collect();
+
+    let mut remaining = Vec::new();
+    for (entity, mut debris) in debris_query.iter_mut() {
+        debris.age += time.delta_seconds();
+        if held.contains(&entity) {
+            continue;
+        }
+        if debris.age >= debris.lifetime.unwrap_or(settings.lifetime) {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            remaining.push((entity, debris.age));
+        }
+    }
+
+    if remaining.len() > settings.budget {
+        remaining.sort_by(|(_, age), (_, other_age)| other_age.total_cmp(age));
+        for (entity, _) in remaining.drain(..remaining.len() - settings.budget) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
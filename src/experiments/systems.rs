@@ -6,8 +6,14 @@ use bevy_rapier3d::prelude::*;
 pub struct Thing1;
 
 const SPAWN_POINT: Vec3 = Vec3::new(3.0, 2.0, 5.0);
+const PLATFORM_POINT: Vec3 = Vec3::new(6.0, 0.3, 5.0);
 
-pub fn spawn_experiment(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn spawn_experiment(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
     let box_gltf = asset_server.load("models/box-small.glb#Scene0");
     let size = 0.25;
 
@@ -52,6 +58,26 @@ pub fn spawn_experiment(mut commands: Commands, asset_server: Res<AssetServer>)
         })
         .id();
 
+    // A slab orbiting the same pivot as the cube, to stand and ride on.
+    let platform_size = Vec3::new(1.5, 0.1, 1.5);
+    commands.spawn((
+        PbrBundle {
+            transform: Transform::from_translation(PLATFORM_POINT),
+            mesh: meshes
+                .add(shape::Box::new(platform_size.x, platform_size.y, platform_size.z).into()),
+            material: materials.add(Color::rgb(0.4, 0.4, 0.6).into()),
+            ..default()
+        },
+        Collider::cuboid(
+            platform_size.x * 0.5,
+            platform_size.y * 0.5,
+            platform_size.z * 0.5,
+        ),
+        RigidBody::KinematicPositionBased,
+        Name::new("platform"),
+        Thing1,
+    ));
+
     let joint = FixedJointBuilder::new().local_anchor1(Vec3::new(1.5, 0.0, 0.0));
     // .local_basis1(Quat::from_rotation_y(TAU * 0.5));
     commands
@@ -0,0 +1,317 @@
+use bevy::prelude::*;
+use bevy_fps_controller::controller::LogicalPlayer;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::damage::{apply_damage, handle_deaths, DamageEvent, DamageKind, DeathEvent};
+use crate::player::player_components::Grabber;
+use crate::MainCamera;
+
+const DEBUG_EXPLOSION_KEY: KeyCode = KeyCode::X;
+const MAX_DEBUG_DISTANCE: f32 = 30.0;
+/// How long an explosion stays visible as a gizmo, in seconds.
+const GIZMO_DURATION: f32 = 0.4;
+/// How far in front of the aimed-at surface the debug explosion goes off.
+const SURFACE_OFFSET: f32 = 0.05;
+
+/// Radial impulses that are shielded by whatever stands between the blast and a body.
+pub struct ExplosionPlugin;
+
+impl Plugin for ExplosionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ExplosionEvent>()
+            .init_resource::<ExplosionSettings>()
+            .register_type::<ExplosionSettings>()
+            // Read deaths in the frame they happen, before the dead barrel is despawned.
+            .add_systems(
+                Update,
+                explode_on_death.after(apply_damage).before(handle_deaths),
+            )
+            .add_systems(Update, debug_explosion_at_crosshair)
+            .add_systems(
+                Update,
+                apply_explosions
+                    .after(explode_on_death)
+                    .after(debug_explosion_at_crosshair),
+            )
+            .add_systems(Update, draw_explosions.after(apply_explosions));
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct ExplosionSettings {
+    /// Impulse (N·s) a held object has to receive to be knocked out of the hand.
+    pub release_impulse: f32,
+    /// Damage per N·s of impulse a damageable body receives.
+    pub damage_per_impulse: f32,
+    /// Fraction of the impulse that is pointed upwards, which makes blasts look livelier.
+    pub upward_bias: f32,
+    pub debug_radius: f32,
+    pub debug_strength: f32,
+}
+
+impl Default for ExplosionSettings {
+    fn default() -> Self {
+        Self {
+            release_impulse: 2.0,
+            damage_per_impulse: 4.0,
+            upward_bias: 0.3,
+            debug_radius: 4.0,
+            debug_strength: 15.0,
+        }
+    }
+}
+
+/// Sets off an explosion at `center`.
+#[derive(Event, Debug, Clone)]
+pub struct ExplosionEvent {
+    pub center: Vec3,
+    pub radius: f32,
+    /// Impulse in N·s at the center, falling off linearly to zero at `radius`.
+    pub strength: f32,
+    /// Entity causing the explosion, it neither receives nor blocks the blast.
+    pub source: Option<Entity>,
+}
+
+/// Makes an entity explode when it dies, e.g. an explosive barrel.
+#[derive(Component, Schematic, Reflect, Debug, Clone)]
+#[reflect(Schematic)]
+pub struct Explosive {
+    pub radius: f32,
+    pub strength: f32,
+}
+
+fn explode_on_death(
+    mut death_events: EventReader<DeathEvent>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    explosive_query: Query<(&Explosive, &GlobalTransform)>,
+) {
+    for death in death_events.iter() {
+        let Ok((explosive, transform)) = explosive_query.get(death.entity) else {
+            continue;
+        };
+        explosion_events.send(ExplosionEvent {
+            center: transform.translation(),
+            radius: explosive.radius,
+            strength: explosive.strength,
+            source: Some(death.entity),
+        });
+    }
+}
+
+fn debug_explosion_at_crosshair(
+    key: Res<Input<KeyCode>>,
+    settings: Res<ExplosionSettings>,
+    rapier_context: Res<RapierContext>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+    player_query: Query<Entity, With<LogicalPlayer>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    if !key.just_pressed(DEBUG_EXPLOSION_KEY) {
+        return;
+    }
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    let mut filter = QueryFilter::default().exclude_sensors();
+    if let Ok(player) = player_query.get_single() {
+        filter = filter.exclude_rigid_body(player);
+    }
+    let Some((_, hit)) = rapier_context.cast_ray_and_get_normal(
+        camera.translation,
+        camera.forward(),
+        MAX_DEBUG_DISTANCE,
+        true,
+        filter,
+    ) else {
+        return;
+    };
+    explosion_events.send(ExplosionEvent {
+        // Off the surface, so the surface itself does not shield anything from the blast.
+        center: hit.point + hit.normal * SURFACE_OFFSET,
+        radius: settings.debug_radius,
+        strength: settings.debug_strength,
+        source: None,
+    });
+}
+
+fn apply_explosions(
+    mut commands: Commands,
+    mut explosion_events: EventReader<ExplosionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    settings: Res<ExplosionSettings>,
+    mut rapier_context: ResMut<RapierContext>,
+    mut body_query: Query<(
+        &RigidBody,
+        &GlobalTransform,
+        Option<&mut ExternalImpulse>,
+        Option<&RapierRigidBodyHandle>,
+    )>,
+    mut grabber_query: Query<(Entity, &mut Grabber)>,
+) {
+    for explosion in explosion_events.iter() {
+        if explosion.radius <= 0.0 {
+            continue;
+        }
+
+        let mut filter = QueryFilter::default().exclude_sensors();
+        if let Some(source) = explosion.source {
+            filter = filter.exclude_rigid_body(source).exclude_collider(source);
+        }
+
+        let mut bodies = Vec::new();
+        rapier_context.intersections_with_shape(
+            explosion.center,
+            Quat::IDENTITY,
+            &Collider::ball(explosion.radius),
+            filter,
+            |collider| {
+                let body = rapier_context.collider_parent(collider).unwrap_or(collider);
+                if !bodies.contains(&body) {
+                    bodies.push(body);
+                }
+                true
+            },
+        );
+
+        let mut woken = Vec::new();
+        for body in bodies {
+            let Ok((rigid_body, transform, external_impulse, handle)) = body_query.get_mut(body)
+            else {
+                continue;
+            };
+            if *rigid_body != RigidBody::Dynamic {
+                continue;
+            }
+
+            let offset = transform.translation() - explosion.center;
+            let distance = offset.length();
+            let falloff = (1.0 - distance / explosion.radius).clamp(0.0, 1.0);
+            if falloff <= 0.0 {
+                continue;
+            }
+            if is_occluded(&rapier_context, explosion.center, offset, body, filter) {
+                continue;
+            }
+
+            let direction = if distance > f32::EPSILON {
+                offset / distance
+            } else {
+                Vec3::Y
+            };
+            let direction = (direction + Vec3::Y * settings.upward_bias).normalize();
+            let impulse = direction * explosion.strength * falloff;
+            match external_impulse {
+                Some(mut external_impulse) => external_impulse.impulse += impulse,
+                None => {
+                    commands.entity(body).insert(ExternalImpulse {
+                        impulse,
+                        ..default()
+                    });
+                }
+            }
+            if let Some(handle) = handle {
+                woken.push(handle.0);
+            }
+
+            damage_events.send(DamageEvent {
+                target: body,
+                amount: impulse.length() * settings.damage_per_impulse,
+                kind: DamageKind::Explosion,
+                source: explosion.source,
+            });
+
+            if impulse.length() >= settings.release_impulse {
+                release_from_grabbers(&mut commands, &mut grabber_query, body);
+            }
+        }
+
+        for handle in woken {
+            if let Some(body) = rapier_context.bodies.get_mut(handle) {
+                body.wake_up(true);
+            }
+        }
+    }
+}
+
+/// Whether something other than `body` blocks the line from the explosion center to it.
+///
+/// Colliders the center lies in or on, like the surface an explosion was set off on, do
+/// not shield anything.
+fn is_occluded(
+    rapier_context: &RapierContext,
+    center: Vec3,
+    offset: Vec3,
+    body: Entity,
+    filter: QueryFilter,
+) -> bool {
+    let distance = offset.length();
+    if distance <= f32::EPSILON {
+        return false;
+    }
+    let mut surrounding = Vec::new();
+    rapier_context.intersections_with_point(center, filter, |collider| {
+        surrounding.push(collider);
+        true
+    });
+    if surrounding.iter().any(|collider| {
+        rapier_context
+            .collider_parent(*collider)
+            .unwrap_or(*collider)
+            == body
+    }) {
+        return false;
+    }
+    let outside = |collider: Entity| !surrounding.contains(&collider);
+    let Some((hit, _)) = rapier_context.cast_ray(
+        center,
+        offset / distance,
+        distance,
+        true,
+        filter.predicate(&outside),
+    ) else {
+        return false;
+    };
+    rapier_context.collider_parent(hit).unwrap_or(hit) != body
+}
+
+fn release_from_grabbers(
+    commands: &mut Commands,
+    grabber_query: &mut Query<(Entity, &mut Grabber)>,
+    body: Entity,
+) {
+    for (grabber_entity, mut grabber) in grabber_query.iter_mut() {
+        if grabber.attracted_target == Some(body) {
+            grabber.attracted_target = None;
+        }
+        if grabber.grabbed_entity == Some(body) {
+            grabber.grabbed_entity = None;
+            commands.entity(grabber_entity).remove::<ImpulseJoint>();
+        }
+    }
+}
+
+fn draw_explosions(
+    mut explosion_events: EventReader<ExplosionEvent>,
+    mut recent: Local<Vec<(ExplosionEvent, f32)>>,
+    time: Res<Time>,
+    mut gizmos: Gizmos,
+) {
+    recent.extend(
+        explosion_events
+            .iter()
+            .map(|explosion| (explosion.clone(), 0.0)),
+    );
+    recent.retain_mut(|(explosion, age)| {
+        *age += time.delta_seconds();
+        let progress = (*age / GIZMO_DURATION).min(1.0);
+        gizmos.sphere(
+            explosion.center,
+            Quat::IDENTITY,
+            explosion.radius * progress,
+            Color::ORANGE_RED,
+        );
+        *age < GIZMO_DURATION
+    });
+}
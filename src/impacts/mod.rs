@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier3d::prelude::*;
+
+use crate::debug::time_controls::physics_step_seconds;
+
+/// Turns Rapier contact force events into classified [`ImpactEvent`]s.
+pub struct ImpactPlugin;
+
+impl Plugin for ImpactPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ImpactEvent>()
+            .init_resource::<ImpactSettings>()
+            .init_resource::<LastPhysicsStep>()
+            .register_type::<ImpactSettings>()
+            .add_systems(Update, enable_contact_force_events)
+            .add_systems(
+                PostUpdate,
+                record_physics_step.after(PhysicsSet::StepSimulation),
+            )
+            // Contact force events are written during `PostUpdate`, aggregating them in
+            // `PreUpdate` makes the impacts of the last physics step available to `Update`.
+            .add_systems(PreUpdate, aggregate_contact_forces);
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct ImpactSettings {
+    /// Contact forces below this (in N) are not reported by Rapier at all.
+    pub min_force: f32,
+    /// Impulse (N·s) from which a contact counts as a [`ImpactKind::Hit`].
+    pub hit_impulse: f32,
+    /// Impulse (N·s) from which a contact counts as a [`ImpactKind::Slam`].
+    pub slam_impulse: f32,
+    /// Relative speed (m/s) from which a contact counts as a [`ImpactKind::Hit`].
+    pub hit_speed: f32,
+    /// Relative speed (m/s) from which a contact counts as a [`ImpactKind::Slam`].
+    pub slam_speed: f32,
+}
+
+impl Default for ImpactSettings {
+    fn default() -> Self {
+        Self {
+            min_force: 5.0,
+            hit_impulse: 1.0,
+            slam_impulse: 6.0,
+            hit_speed: 2.0,
+            slam_speed: 8.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ImpactKind {
+    Tap,
+    Hit,
+    Slam,
+}
+
+/// One impact between two bodies during a physics step.
+#[derive(Event, Debug, Clone)]
+pub struct ImpactEvent {
+    /// Rigid body (or lone collider) of the first participant.
+    pub entity: Entity,
+    /// Rigid body (or lone collider) of the second participant.
+    pub other: Entity,
+    /// Average world-space contact point.
+    pub position: Vec3,
+    /// World-space contact normal, pointing from `entity` towards `other`.
+    pub normal: Vec3,
+    /// Summed contact force magnitude in N.
+    pub force: f32,
+    /// Contact impulse over the step in N·s.
+    pub impulse: f32,
+    /// Speed at which the contact points approached each other along the normal.
+    pub relative_speed: f32,
+    pub kind: ImpactKind,
+}
+
+impl ImpactEvent {
+    pub fn involves(&self, entity: Entity) -> bool {
+        self.entity == entity || self.other == entity
+    }
+
+    /// The participant that is not `entity`.
+    pub fn other_than(&self, entity: Entity) -> Entity {
+        if self.entity == entity {
+            self.other
+        } else {
+            self.entity
+        }
+    }
+}
+
+/// Enables contact force events on every collider that belongs to a dynamic body.
+fn enable_contact_force_events(
+    mut commands: Commands,
+    settings: Res<ImpactSettings>,
+    collider_query: Query<
+        (
+            Entity,
+            Option<&ActiveEvents>,
+            Option<&ContactForceEventThreshold>,
+        ),
+        Added<Collider>,
+    >,
+    body_query: Query<&RigidBody>,
+    parent_query: Query<&Parent>,
+) {
+    for (entity, active_events, threshold) in collider_query.iter() {
+        let body = std::iter::once(entity)
+            .chain(parent_query.iter_ancestors(entity))
+            .find_map(|ancestor| body_query.get(ancestor).ok());
+        if body != Some(&RigidBody::Dynamic) {
+            continue;
+        }
+
+        let active_events = active_events.copied().unwrap_or(ActiveEvents::empty());
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(active_events | ActiveEvents::CONTACT_FORCE_EVENTS);
+        if threshold.is_none() {
+            entity_commands.insert(ContactForceEventThreshold(settings.min_force));
+        }
+    }
+}
+
+struct ContactAggregate {
+    colliders: (Entity, Entity),
+    force: f32,
+}
+
+/// Seconds simulated by the physics step whose contact forces are aggregated next.
+#[derive(Resource, Default, Debug)]
+struct LastPhysicsStep(f32);
+
+/// Remembers the step length while the [`Time`] and Rapier configuration it used are current.
+fn record_physics_step(
+    time: Res<Time>,
+    rapier_config: Res<RapierConfiguration>,
+    mut last_step: ResMut<LastPhysicsStep>,
+) {
+    last_step.0 = physics_step_seconds(&rapier_config, &time);
+}
+
+fn aggregate_contact_forces(
+    mut contact_force_events: EventReader<ContactForceEvent>,
+    mut impact_events: EventWriter<ImpactEvent>,
+    rapier_context: Res<RapierContext>,
+    settings: Res<ImpactSettings>,
+    body_query: Query<(&GlobalTransform, Option<&Velocity>)>,
+    last_step: Res<LastPhysicsStep>,
+) {
+    let mut aggregates: HashMap<(Entity, Entity), ContactAggregate> = HashMap::default();
+    for contact_force_event in contact_force_events.iter() {
+        let collider1 = contact_force_event.collider1;
+        let collider2 = contact_force_event.collider2;
+        let body1 = rapier_context
+            .collider_parent(collider1)
+            .unwrap_or(collider1);
+        let body2 = rapier_context
+            .collider_parent(collider2)
+            .unwrap_or(collider2);
+        let (key, colliders) = if body1 <= body2 {
+            ((body1, body2), (collider1, collider2))
+        } else {
+            ((body2, body1), (collider2, collider1))
+        };
+        let aggregate = aggregates.entry(key).or_insert_with(|| ContactAggregate {
+            colliders,
+            force: 0.0,
+        });
+        aggregate.force += contact_force_event.total_force_magnitude;
+    }
+
+    for ((entity, other), aggregate) in aggregates {
+        let (collider1, collider2) = aggregate.colliders;
+        let Some(contact_pair) = rapier_context.contact_pair(collider1, collider2) else {
+            continue;
+        };
+        let flip = contact_pair.collider1() != collider1;
+
+        let mut position = Vec3::ZERO;
+        let mut normal = Vec3::ZERO;
+        let mut points = 0;
+        for manifold in contact_pair.manifolds() {
+            normal += manifold.normal();
+            for contact in manifold.solver_contacts() {
+                position += contact.point();
+                points += 1;
+            }
+        }
+        if points == 0 {
+            continue;
+        }
+        position /= points as f32;
+        let normal = normal.normalize_or_zero();
+        let normal = if flip { -normal } else { normal };
+
+        let point_velocity = |body: Entity| {
+            body_query
+                .get(body)
+                .ok()
+                .and_then(|(transform, velocity)| {
+                    let velocity = velocity?;
+                    let lever = position - transform.translation();
+                    Some(velocity.linvel + velocity.angvel.cross(lever))
+                })
+                .unwrap_or(Vec3::ZERO)
+        };
+        let relative_speed = (point_velocity(entity) - point_velocity(other))
+            .dot(normal)
+            .abs();
+        let impulse = aggregate.force * last_step.0;
+
+        let kind = if impulse >= settings.slam_impulse || relative_speed >= settings.slam_speed {
+            ImpactKind::Slam
+        } else if impulse >= settings.hit_impulse || relative_speed >= settings.hit_speed {
+            ImpactKind::Hit
+        } else {
+            ImpactKind::Tap
+        };
+
+        impact_events.send(ImpactEvent {
+            entity,
+            other,
+            position,
+            normal,
+            force: aggregate.force,
+            impulse,
+            relative_speed,
+            kind,
+        });
+    }
+}
@@ -1,25 +1,48 @@
 mod components;
+mod damage;
+mod debug;
+mod destruction;
 mod experiments;
+mod explosions;
+mod impacts;
 mod player;
+mod prefabs;
 
+use crate::components::collider_shapes::ColliderShapesPlugin;
+use crate::components::joints::JointsPlugin;
+use crate::components::mesh_collider::*;
 use crate::components::rapier_helpers::*;
-use crate::components::SmallBox;
+use crate::components::small_box::SmallBoxPlugin;
+use crate::components::{NameProto, Weapon};
+use crate::damage::{Damageable, DeathBehavior, Health, Projectile, Resistances};
 use bevy::asset::ChangeWatcher;
 use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
 use bevy::prelude::*;
+#[cfg(feature = "editor")]
 use bevy_editor_pls::prelude::*;
 use bevy_fps_controller::controller::*;
 use bevy_proto::prelude::*;
 use bevy_rapier3d::prelude::*;
 use bevy_sprite3d::Sprite3dPlugin;
+use damage::DamagePlugin;
+use debug::DebugPlugin;
+use destruction::{DestructionPlugin, Fracturable, FracturePattern, FragmentProto};
 use experiments::ExperimentsPlugin;
+use explosions::{ExplosionPlugin, Explosive};
+use impacts::ImpactPlugin;
 use player::player_components::*;
+use player::mantle::Climbable;
 use player::LocomotionPlugin;
+use prefabs::{spawn_prefab, PrefabsPlugin};
 use std::time::Duration;
 
 fn main() {
     color_eyre::install().unwrap();
 
+    if std::env::args().any(|arg| arg == prefabs::validation::VALIDATE_PREFABS_ARG) {
+        std::process::exit(prefabs::validation::run());
+    }
+
     let mut app = App::new();
     app.add_plugins(DefaultPlugins.build().set(AssetPlugin {
         // Enable hot-reloading of assets:
@@ -28,25 +51,72 @@ fn main() {
     }))
     .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
     .add_plugins(RapierDebugRenderPlugin::default())
-    .add_plugins(EditorPlugin::default())
     .add_plugins(ProtoPlugin::default())
     .add_plugins(FpsControllerPlugin)
     .add_plugins(FrameTimeDiagnosticsPlugin)
     .add_plugins(LocomotionPlugin)
     .add_plugins(Sprite3dPlugin)
     .add_plugins(ExperimentsPlugin)
+    .add_plugins(PrefabsPlugin)
+    .add_plugins(ImpactPlugin)
+    .add_plugins(DamagePlugin)
+    .add_plugins(DestructionPlugin)
+    .add_plugins(ExplosionPlugin)
+    .add_plugins(DebugPlugin)
     .insert_resource(AmbientLight {
         color: Color::WHITE,
         brightness: 0.5,
     })
     .add_systems(Startup, load)
     .add_systems(Startup, setup)
-    .add_plugins(RapierHelperPlugin)
-    .register_type::<SmallBox>()
-    .register_type::<Playable>();
+    .add_systems(
+        Update,
+        spawn_props.run_if(
+            prototypes_ready([
+                "small_box",
+                "blaster",
+                "target",
+                "stone_block",
+                "explosive_barrel",
+            ])
+            .and_then(run_once()),
+        ),
+    )
+    .add_plugins(PrototypeSchematicsPlugin);
+    #[cfg(feature = "editor")]
+    app.add_plugins(EditorPlugin::default());
     app.run();
 }
 
+/// Everything prototype files can reference, shared by the game and the prefab validation.
+pub struct PrototypeSchematicsPlugin;
+
+impl Plugin for PrototypeSchematicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RapierHelperPlugin)
+            .add_plugins(ColliderShapesPlugin)
+            .add_plugins(MeshColliderPlugin)
+            .add_plugins(JointsPlugin)
+            .add_plugins(SmallBoxPlugin)
+            .register_type::<NameProto>()
+            .register_type::<Weapon>()
+            .register_type::<Health>()
+            .register_type::<Damageable>()
+            .register_type::<Resistances>()
+            .register_type::<DeathBehavior>()
+            .register_type::<Projectile>()
+            .register_type::<Fracturable>()
+            .register_type::<FracturePattern>()
+            .register_type::<FragmentProto>()
+            .register_type::<Vec<FragmentProto>>()
+            .register_type::<Explosive>()
+            .register_type::<Climbable>()
+            .register_type::<Grabbable>()
+            .register_type::<PIDControllerProto>()
+            .register_type::<Playable>();
+    }
+}
+
 // A schematic can be pretty much anything that mutates the world.
 // The simplest type of a schematic is just a regular Bevy component.
 // For components, we can simply add the `Schematic` derive:
@@ -58,6 +128,7 @@ fn main() {
 #[reflect(Schematic)]
 struct Playable;
 
+/// The player's eye, everything the player aims with looks from here.
 #[derive(Component, Default, Reflect)]
 pub struct MainCamera;
 
@@ -70,7 +141,6 @@ fn setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
 ) {
     let plane_size = Vec3::new(100.0, 0.1, 100.0);
     // plane
@@ -92,11 +162,6 @@ fn setup(
             });
         });
 
-    // spawn_small_box(&mut commands, asset_server);
-    spawn_blaster(Vec3::splat(3.0), &mut commands, asset_server.as_ref());
-    spawn_small_box(Vec3::splat(1.0), &mut commands, asset_server.as_ref());
-    spawn_small_box(Vec3::splat(2.0), &mut commands, asset_server.as_ref());
-
     // light
     commands.spawn(PointLightBundle {
         point_light: PointLight {
@@ -107,10 +172,10 @@ fn setup(
         transform: Transform::from_xyz(4.0, 8.0, 4.0),
         ..default()
     });
-    // camera
+    // player eye, the scene is rendered by the camera rig's `ViewCamera`
     commands
         .spawn((
-            Camera3dBundle {
+            SpatialBundle {
                 transform: Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
                 ..default()
             },
@@ -139,68 +204,23 @@ fn setup(
             ));
         });
 }
-#[allow(dead_code)]
-fn spawn_small_box(position: Vec3, commands: &mut Commands, asset_server: &AssetServer) {
-    let box_gltf = asset_server.load("models/box-small.glb#Scene0");
-    let size = 0.25;
-    commands
-        .spawn((
-            SpatialBundle {
-                transform: Transform::from_translation(position),
-                ..Default::default()
-            },
-            Collider::cuboid(size, size, size),
-            RigidBody::Dynamic,
-            Name::new("little_cube"),
-            CubeCollider {
-                size: Vec3::splat(size),
-            },
-            Velocity { ..default() },
-            Grabbable,
-            PIDController::new(0.7, 0.0, 0.3),
-        ))
-        .with_children(|commands| {
-            commands.spawn(SceneBundle {
-                scene: box_gltf,
-                transform: Transform::from_xyz(0.0, -0.25, 0.0),
-                ..default()
-            });
-        });
-}
 
-fn spawn_blaster(position: Vec3, commands: &mut Commands, asset_server: &AssetServer) {
-    commands
-        .spawn((
-            SpatialBundle {
-                transform: Transform::from_translation(position),
-                ..Default::default()
-            },
-            RigidBody::Dynamic,
-            Name::new("blaster"),
-            Grabbable,
-            Velocity { ..default() },
-            PIDController::new(1.7, 0.0, 0.3),
-        ))
-        .with_children(|commands| {
-            commands.spawn(SceneBundle {
-                scene: asset_server.load("models/blasterB.glb#Scene0"),
-                transform: Transform::from_xyz(0.0, 0.0, 0.0),
-                ..default()
-            });
-            commands.spawn((
-                Name::new("top_collider"),
-                SpatialBundle {
-                    transform: Transform::from_xyz(0.0, 0.04, 0.0),
-                    ..default()
-                },
-                Collider::cuboid(0.1 * 0.5, 0.17 * 0.5, 0.41 * 0.5),
-            ));
-            commands.spawn((
-                SpatialBundle {
-                    transform: Transform::from_xyz(0.0, -0.08, -0.13),
-                    ..default()
-                },
-                Collider::cuboid(0.1 * 0.5, 0.13 * 0.5, 0.13 * 0.5),
-            ));
-        });
+/// Spawns the interactable props of the test scene once their prototypes are loaded.
+fn spawn_props(mut commands: ProtoCommands) {
+    let props = [
+        ("blaster", Vec3::splat(3.0)),
+        ("small_box", Vec3::splat(1.0)),
+        ("small_box", Vec3::splat(2.0)),
+        ("target", Vec3::new(-3.0, 1.0, -3.0)),
+        ("stone_block", Vec3::new(3.0, 1.0, -3.0)),
+        ("explosive_barrel", Vec3::new(0.0, 1.0, -5.0)),
+        ("explosive_barrel", Vec3::new(1.0, 1.0, -5.5)),
+    ];
+    for (prototype, position) in props {
+        spawn_prefab(
+            &mut commands,
+            prototype,
+            Transform::from_translation(position),
+        );
+    }
 }
@@ -0,0 +1,367 @@
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy_fps_controller::controller::{FpsController, LogicalPlayer};
+use bevy_rapier3d::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+use super::player_components::{Grabbable, Grabber, RightHand};
+use super::stance::stance_camera;
+use crate::MainCamera;
+
+const CAMERA_MODE_KEY: KeyCode = KeyCode::F1;
+const ORBIT_TARGET_KEY: KeyCode = KeyCode::F2;
+/// Furthest the player's aim is followed when looking at nothing.
+const MAX_AIM_DISTANCE: f32 = 30.0;
+
+/// Renders the scene from a [`ViewCamera`] that is placed according to the [`CameraMode`].
+///
+/// The player keeps aiming, grabbing and holding from their eye, the [`MainCamera`],
+/// which stays where the controller puts it in every mode.
+pub struct CameraRigPlugin;
+
+impl Plugin for CameraRigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraMode>()
+            .init_resource::<CameraRigSettings>()
+            .init_resource::<OrbitCamera>()
+            .register_type::<CameraRigSettings>()
+            .register_type::<OrbitCamera>()
+            .add_systems(Startup, spawn_view_camera)
+            .add_systems(Update, (cycle_camera_mode, select_orbit_target).chain())
+            .add_systems(
+                Update,
+                orbit_input
+                    .after(select_orbit_target)
+                    .run_if(resource_equals(CameraMode::Orbit)),
+            )
+            .add_systems(
+                Update,
+                place_view_camera
+                    .after(stance_camera)
+                    .after(orbit_input)
+                    .before(PhysicsSet::SyncBackend),
+            );
+    }
+}
+
+/// Where the scene is viewed from.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    /// From the player's eye.
+    #[default]
+    FirstPerson,
+    /// Over the player's shoulder, looking where they look.
+    ThirdPerson,
+    /// Detached, orbiting the [`OrbitCamera`] target or flying freely without one.
+    Orbit,
+}
+
+impl CameraMode {
+    fn next(&self) -> Self {
+        match self {
+            CameraMode::FirstPerson => CameraMode::ThirdPerson,
+            CameraMode::ThirdPerson => CameraMode::Orbit,
+            CameraMode::Orbit => CameraMode::FirstPerson,
+        }
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct CameraRigSettings {
+    /// Pivot of the third-person boom relative to the eye.
+    pub shoulder_offset: Vec3,
+    pub boom_length: f32,
+    /// Radius of the probe that keeps the boom out of geometry.
+    pub boom_radius: f32,
+    /// Radians of orbit per pixel of mouse motion.
+    pub orbit_sensitivity: f32,
+    /// Orbit distance change per scroll wheel line.
+    pub orbit_zoom_step: f32,
+    pub min_orbit_distance: f32,
+    pub max_orbit_distance: f32,
+    /// Speed of the free camera without an orbit target.
+    pub free_speed: f32,
+}
+
+impl Default for CameraRigSettings {
+    fn default() -> Self {
+        Self {
+            shoulder_offset: Vec3::new(0.4, 0.1, 0.0),
+            boom_length: 2.5,
+            boom_radius: 0.2,
+            orbit_sensitivity: 0.003,
+            orbit_zoom_step: 0.5,
+            min_orbit_distance: 1.0,
+            max_orbit_distance: 30.0,
+            free_speed: 8.0,
+        }
+    }
+}
+
+/// State of the detached camera.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct OrbitCamera {
+    /// Entity being followed, the camera flies freely around `focus` without one.
+    pub target: Option<Entity>,
+    pub focus: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub distance: f32,
+    /// Whether the player controller took input before the orbit camera took over.
+    restore_input: bool,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            target: None,
+            focus: Vec3::ZERO,
+            yaw: 0.0,
+            pitch: -0.4,
+            distance: 6.0,
+            restore_input: false,
+        }
+    }
+}
+
+/// Run condition for the player's mouse input, which the orbit camera takes over.
+pub fn camera_not_orbiting(camera_mode: Res<CameraMode>) -> bool {
+    *camera_mode != CameraMode::Orbit
+}
+
+/// The camera the scene is rendered with.
+#[derive(Component, Default, Reflect)]
+pub struct ViewCamera;
+
+fn spawn_view_camera(mut commands: Commands) {
+    commands.spawn((
+        Camera3dBundle::default(),
+        ViewCamera,
+        Name::new("view_camera"),
+    ));
+}
+
+/// Switches the [`CameraMode`], the player stops taking input while the orbit camera is used.
+fn cycle_camera_mode(
+    key: Res<Input<KeyCode>>,
+    mut camera_mode: ResMut<CameraMode>,
+    mut orbit: ResMut<OrbitCamera>,
+    mut controller_query: Query<&mut FpsController>,
+    player_query: Query<Entity, With<LogicalPlayer>>,
+    hand_query: Query<&Grabber, With<RightHand>>,
+    view_query: Query<&Transform, With<ViewCamera>>,
+) {
+    if !key.just_pressed(CAMERA_MODE_KEY) {
+        return;
+    }
+    *camera_mode = camera_mode.next();
+    info!("camera mode: {:?}", *camera_mode);
+
+    match *camera_mode {
+        CameraMode::Orbit => {
+            // Start on whatever the player is looking at, or the player themselves.
+            orbit.target = hand_query
+                .get_single()
+                .ok()
+                .and_then(|grabber| grabber.potential_target)
+                .or_else(|| player_query.get_single().ok());
+            if let Ok(view) = view_query.get_single() {
+                let (yaw, pitch, _) = view.rotation.to_euler(EulerRot::YXZ);
+                orbit.yaw = yaw;
+                orbit.pitch = pitch;
+            }
+            for mut controller in controller_query.iter_mut() {
+                orbit.restore_input = controller.enable_input;
+                controller.enable_input = false;
+            }
+        }
+        CameraMode::FirstPerson => {
+            for mut controller in controller_query.iter_mut() {
+                controller.enable_input = orbit.restore_input;
+            }
+        }
+        CameraMode::ThirdPerson => {}
+    }
+}
+
+/// Cycles the orbit target through the player and all grabbable objects, then free flight.
+fn select_orbit_target(
+    key: Res<Input<KeyCode>>,
+    camera_mode: Res<CameraMode>,
+    mut orbit: ResMut<OrbitCamera>,
+    candidate_query: Query<Entity, Or<(With<LogicalPlayer>, With<Grabbable>)>>,
+    name_query: Query<&Name>,
+) {
+    if *camera_mode != CameraMode::Orbit || !key.just_pressed(ORBIT_TARGET_KEY) {
+        return;
+    }
+    let mut candidates: Vec<Entity> = candidate_query.iter().collect();
+    candidates.sort();
+    orbit.target = match orbit.target {
+        None => candidates.first().copied(),
+        Some(current) => candidates
+            .into_iter()
+            .find(|candidate| *candidate > current),
+    };
+    match orbit.target {
+        Some(target) => info!(
+            "orbiting {}",
+            name_query
+                .get(target)
+                .map_or_else(|_| format!("{:?}", target), |name| name.to_string())
+        ),
+        None => info!("free camera"),
+    }
+}
+
+/// Mouse orbits, the scroll wheel zooms and without a target the movement keys fly the focus.
+fn orbit_input(
+    key: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    settings: Res<CameraRigSettings>,
+    mut orbit: ResMut<OrbitCamera>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    controller_query: Query<&FpsController>,
+) {
+    for motion in mouse_motion.iter() {
+        orbit.yaw -= motion.delta.x * settings.orbit_sensitivity;
+        orbit.pitch = (orbit.pitch - motion.delta.y * settings.orbit_sensitivity)
+            .clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
+    }
+    for wheel in mouse_wheel.iter() {
+        let lines = match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / 16.0,
+        };
+        orbit.distance = (orbit.distance - lines * settings.orbit_zoom_step)
+            .clamp(settings.min_orbit_distance, settings.max_orbit_distance);
+    }
+
+    if orbit.target.is_some() {
+        return;
+    }
+    // Reuse the player's movement bindings for the free camera.
+    let Ok(controller) = controller_query.get_single() else {
+        return;
+    };
+    let rotation = Quat::from_rotation_y(orbit.yaw);
+    let mut direction = Vec3::ZERO;
+    if key.pressed(controller.key_forward) {
+        direction += rotation * Vec3::NEG_Z;
+    }
+    if key.pressed(controller.key_back) {
+        direction += rotation * Vec3::Z;
+    }
+    if key.pressed(controller.key_left) {
+        direction += rotation * Vec3::NEG_X;
+    }
+    if key.pressed(controller.key_right) {
+        direction += rotation * Vec3::X;
+    }
+    if key.pressed(controller.key_up) {
+        direction += Vec3::Y;
+    }
+    if key.pressed(controller.key_down) {
+        direction += Vec3::NEG_Y;
+    }
+    orbit.focus += direction.normalize_or_zero() * settings.free_speed * time.delta_seconds();
+}
+
+fn place_view_camera(
+    camera_mode: Res<CameraMode>,
+    settings: Res<CameraRigSettings>,
+    rapier_context: Res<RapierContext>,
+    mut orbit: ResMut<OrbitCamera>,
+    eye_query: Query<&Transform, (With<MainCamera>, Without<ViewCamera>)>,
+    player_query: Query<Entity, With<LogicalPlayer>>,
+    target_query: Query<&GlobalTransform>,
+    mut view_query: Query<&mut Transform, With<ViewCamera>>,
+) {
+    let (Ok(eye), Ok(mut view)) = (eye_query.get_single(), view_query.get_single_mut()) else {
+        return;
+    };
+
+    match *camera_mode {
+        CameraMode::FirstPerson => *view = *eye,
+        CameraMode::ThirdPerson => {
+            let mut filter = QueryFilter::default().exclude_sensors();
+            if let Ok(player) = player_query.get_single() {
+                filter = filter.exclude_rigid_body(player);
+            }
+            // Swing out to the shoulder first, then back along the boom.
+            let pivot = boom(
+                &rapier_context,
+                filter,
+                settings.boom_radius,
+                eye.translation,
+                eye.rotation * settings.shoulder_offset,
+            );
+            view.translation = boom(
+                &rapier_context,
+                filter,
+                settings.boom_radius,
+                pivot,
+                eye.back() * settings.boom_length,
+            );
+            view.rotation = eye.rotation;
+        }
+        CameraMode::Orbit => {
+            if let Some(target) = orbit.target {
+                match target_query.get(target) {
+                    Ok(transform) => orbit.focus = transform.translation(),
+                    // Keep flying around where the target was last seen.
+                    Err(_) => orbit.target = None,
+                }
+            }
+            view.rotation = Quat::from_euler(EulerRot::YXZ, orbit.yaw, orbit.pitch, 0.0);
+            view.translation = orbit.focus + view.back() * orbit.distance;
+        }
+    }
+}
+
+/// Moves from `start` by `offset`, stopping short of anything in the way.
+fn boom(
+    rapier_context: &RapierContext,
+    filter: QueryFilter,
+    radius: f32,
+    start: Vec3,
+    offset: Vec3,
+) -> Vec3 {
+    let length = offset.length();
+    if length <= f32::EPSILON {
+        return start;
+    }
+    let direction = offset / length;
+    let distance = rapier_context
+        .cast_shape(
+            start,
+            Quat::IDENTITY,
+            direction,
+            &Collider::ball(radius),
+            length,
+            filter,
+        )
+        .map_or(length, |(_, toi)| toi.toi);
+    start + direction * distance
+}
+
+/// Point the player's eye is aiming at.
+pub fn aim_point(rapier_context: &RapierContext, eye: &Transform, player: Option<Entity>) -> Vec3 {
+    let mut filter = QueryFilter::default().exclude_sensors();
+    if let Some(player) = player {
+        filter = filter.exclude_rigid_body(player);
+    }
+    let distance = rapier_context
+        .cast_ray(
+            eye.translation,
+            eye.forward(),
+            MAX_AIM_DISTANCE,
+            true,
+            filter,
+        )
+        .map_or(MAX_AIM_DISTANCE, |(_, toi)| toi);
+    eye.translation + eye.forward() * distance
+}
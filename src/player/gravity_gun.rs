@@ -0,0 +1,294 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::camera_rig::camera_not_orbiting;
+use super::player_components::{Grabber, RightHand};
+use crate::MainCamera;
+
+const TOOL_SWITCH_KEY: KeyCode = KeyCode::G;
+const LEVITATE_KEY: KeyCode = KeyCode::V;
+const GUN_BUTTON: MouseButton = MouseButton::Left;
+const DROP_BUTTON: MouseButton = MouseButton::Right;
+
+/// Gravity gun tool for the right hand, switched with the grabbing hand through [`ActiveTool`].
+pub struct GravityGunPlugin;
+
+impl Plugin for GravityGunPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveTool>()
+            .init_resource::<GravityGunSettings>()
+            .register_type::<GravityGunSettings>()
+            .register_type::<GravityGun>()
+            .add_systems(Update, switch_tool)
+            .add_systems(
+                Update,
+                gravity_gun_input
+                    .after(switch_tool)
+                    .run_if(resource_equals(ActiveTool::GravityGun))
+                    .run_if(camera_not_orbiting),
+            )
+            .add_systems(
+                Update,
+                gravity_gun_hold
+                    .after(gravity_gun_input)
+                    .run_if(resource_equals(ActiveTool::GravityGun)),
+            );
+    }
+}
+
+/// What the right hand does with the mouse buttons.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActiveTool {
+    /// Grab objects with [`Grabber`].
+    #[default]
+    Hand,
+    /// Pull, punt, launch and levitate objects with the [`GravityGun`].
+    GravityGun,
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct GravityGunSettings {
+    /// Speed at which targets are pulled towards the hold point.
+    pub pull_speed: f32,
+    /// Distance to the hold point below which a pulled object is caught.
+    pub catch_distance: f32,
+    /// How fast a held object corrects its offset from the hold point, per second.
+    pub hold_stiffness: f32,
+    pub max_hold_speed: f32,
+    /// Impulse (N·s) of a tap on an object that is not held.
+    pub punt_impulse: f32,
+    /// Speed of a held object after releasing the button.
+    pub launch_speed: f32,
+    /// Presses shorter than this (in seconds) count as a tap.
+    pub tap_time: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// Distance change per scroll wheel line.
+    pub scroll_step: f32,
+}
+
+impl Default for GravityGunSettings {
+    fn default() -> Self {
+        Self {
+            pull_speed: 12.0,
+            catch_distance: 0.5,
+            hold_stiffness: 12.0,
+            max_hold_speed: 20.0,
+            punt_impulse: 6.0,
+            launch_speed: 30.0,
+            tap_time: 0.2,
+            min_distance: 1.5,
+            max_distance: 10.0,
+            scroll_step: 0.5,
+        }
+    }
+}
+
+/// State of the gravity gun, lives next to the [`Grabber`] of the right hand.
+#[derive(Component, Reflect, Debug)]
+pub struct GravityGun {
+    /// Object currently being pulled towards the hold point.
+    pub pulled: Option<Entity>,
+    /// Object floating at the hold point.
+    pub held: Option<Entity>,
+    /// Distance of the hold point in front of the camera.
+    pub hold_distance: f32,
+    /// Levitated objects stay held when the button is released instead of being launched.
+    pub levitating: bool,
+    pressed_for: f32,
+}
+
+impl Default for GravityGun {
+    fn default() -> Self {
+        Self {
+            pulled: None,
+            held: None,
+            hold_distance: 2.5,
+            levitating: false,
+            pressed_for: 0.0,
+        }
+    }
+}
+
+/// Switches between the hand and the gravity gun, handing a held object over.
+fn switch_tool(
+    mut commands: Commands,
+    key: Res<Input<KeyCode>>,
+    mut active_tool: ResMut<ActiveTool>,
+    mut hand_query: Query<(Entity, &mut Grabber, &mut GravityGun), With<RightHand>>,
+) {
+    if !key.just_pressed(TOOL_SWITCH_KEY) {
+        return;
+    }
+    *active_tool = match *active_tool {
+        ActiveTool::Hand => ActiveTool::GravityGun,
+        ActiveTool::GravityGun => ActiveTool::Hand,
+    };
+    let Ok((hand, mut grabber, mut gravity_gun)) = hand_query.get_single_mut() else {
+        return;
+    };
+    match *active_tool {
+        ActiveTool::GravityGun => {
+            if let Some(grabbed) = grabber.grabbed_entity.take() {
+                commands.entity(hand).remove::<ImpulseJoint>();
+                gravity_gun.held = Some(grabbed);
+            }
+            grabber.attracted_target = None;
+        }
+        ActiveTool::Hand => {
+            gravity_gun.pulled = None;
+            gravity_gun.held = None;
+            gravity_gun.levitating = false;
+        }
+    }
+}
+
+fn gravity_gun_input(
+    mouse: Res<Input<MouseButton>>,
+    key: Res<Input<KeyCode>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    time: Res<Time>,
+    settings: Res<GravityGunSettings>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+    mut hand_query: Query<(&Grabber, &mut GravityGun), With<RightHand>>,
+    mut body_query: Query<(&mut Velocity, Option<&mut ExternalImpulse>)>,
+    mut commands: Commands,
+) {
+    let Ok((grabber, mut gravity_gun)) = hand_query.get_single_mut() else {
+        return;
+    };
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+
+    for mouse_wheel in mouse_wheel_events.iter() {
+        let lines = match mouse_wheel.unit {
+            MouseScrollUnit::Line => mouse_wheel.y,
+            MouseScrollUnit::Pixel => mouse_wheel.y / 20.0,
+        };
+        gravity_gun.hold_distance = (gravity_gun.hold_distance + lines * settings.scroll_step)
+            .clamp(settings.min_distance, settings.max_distance);
+    }
+
+    if key.just_pressed(LEVITATE_KEY) {
+        gravity_gun.levitating = !gravity_gun.levitating;
+        if gravity_gun.levitating && gravity_gun.held.is_none() {
+            gravity_gun.held = grabber.potential_target;
+        }
+    }
+
+    // Held objects that no longer exist (e.g. they broke) are let go.
+    if let Some(held) = gravity_gun.held {
+        if body_query.get(held).is_err() {
+            gravity_gun.held = None;
+        }
+    }
+
+    if mouse.just_pressed(DROP_BUTTON) {
+        gravity_gun.held = None;
+        gravity_gun.pulled = None;
+        gravity_gun.levitating = false;
+    }
+
+    if mouse.just_pressed(GUN_BUTTON) {
+        gravity_gun.pressed_for = 0.0;
+        if gravity_gun.held.is_none() {
+            gravity_gun.pulled = grabber.potential_target;
+        }
+    }
+    if mouse.pressed(GUN_BUTTON) {
+        gravity_gun.pressed_for += time.delta_seconds();
+    }
+    if !mouse.just_released(GUN_BUTTON) {
+        return;
+    }
+
+    let tap = gravity_gun.pressed_for < settings.tap_time;
+    gravity_gun.pulled = None;
+    match gravity_gun.held {
+        // Levitated objects are only launched by a deliberate click.
+        Some(_) if gravity_gun.levitating && !tap => {}
+        Some(held) => {
+            if let Ok((mut velocity, _)) = body_query.get_mut(held) {
+                velocity.linvel = camera.forward() * settings.launch_speed;
+            }
+            gravity_gun.held = None;
+            gravity_gun.levitating = false;
+        }
+        None if tap => {
+            let Some(target) = grabber.potential_target else {
+                return;
+            };
+            let impulse = camera.forward() * settings.punt_impulse;
+            match body_query.get_mut(target) {
+                Ok((_, Some(mut external_impulse))) => external_impulse.impulse += impulse,
+                Ok((_, None)) => {
+                    commands.entity(target).insert(ExternalImpulse {
+                        impulse,
+                        ..default()
+                    });
+                }
+                Err(_) => {}
+            }
+        }
+        None => {}
+    }
+}
+
+/// Pulls the target towards the hold point and keeps a held object floating there.
+fn gravity_gun_hold(
+    settings: Res<GravityGunSettings>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+    mut hand_query: Query<(&GlobalTransform, &mut GravityGun), With<RightHand>>,
+    mut body_query: Query<(&Transform, &mut Velocity, Option<&RapierRigidBodyHandle>)>,
+    mut rapier_context: ResMut<RapierContext>,
+    mut gizmos: Gizmos,
+) {
+    let Ok((hand_transform, mut gravity_gun)) = hand_query.get_single_mut() else {
+        return;
+    };
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    let hold_point = camera.translation + camera.forward() * gravity_gun.hold_distance;
+
+    if let Some(pulled) = gravity_gun.pulled {
+        if let Ok((transform, mut velocity, _)) = body_query.get_mut(pulled) {
+            let offset = hold_point - transform.translation;
+            if offset.length() < settings.catch_distance {
+                gravity_gun.held = Some(pulled);
+                gravity_gun.pulled = None;
+            } else {
+                velocity.linvel = offset.normalize() * settings.pull_speed;
+            }
+            gizmos.line(
+                hand_transform.translation(),
+                transform.translation,
+                Color::CYAN,
+            );
+        } else {
+            gravity_gun.pulled = None;
+        }
+    }
+
+    let Some(held) = gravity_gun.held else {
+        return;
+    };
+    let Ok((transform, mut velocity, handle)) = body_query.get_mut(held) else {
+        return;
+    };
+    let offset = hold_point - transform.translation;
+    velocity.linvel = (offset * settings.hold_stiffness).clamp_length_max(settings.max_hold_speed);
+    velocity.angvel *= 0.9;
+    if let Some(body) = handle.and_then(|handle| rapier_context.bodies.get_mut(handle.0)) {
+        body.wake_up(true);
+    }
+    let color = if gravity_gun.levitating {
+        Color::PURPLE
+    } else {
+        Color::CYAN
+    };
+    gizmos.line(hand_transform.translation(), transform.translation, color);
+}
@@ -0,0 +1,257 @@
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy_fps_controller::controller::FpsController;
+use bevy_rapier3d::prelude::*;
+use std::f32::consts::TAU;
+
+use super::camera_rig::camera_not_orbiting;
+use super::gravity_gun::ActiveTool;
+use super::player_components::{Grabber, RightHand};
+
+const ROTATE_KEY: KeyCode = KeyCode::AltLeft;
+const ROTATION_SNAP_KEY: KeyCode = KeyCode::T;
+
+/// Scroll wheel hold distance and mouse rotation for objects held by the right hand.
+pub struct HoldControlsPlugin;
+
+impl Plugin for HoldControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HoldSettings>()
+            .init_resource::<RotationSnap>()
+            .register_type::<HoldSettings>()
+            .add_systems(Update, cycle_rotation_snap)
+            .add_systems(Update, suspend_look_input)
+            .add_systems(
+                Update,
+                (adjust_hold_distance, rotate_held_object)
+                    .run_if(resource_equals(ActiveTool::Hand))
+                    .run_if(camera_not_orbiting),
+            )
+            .add_systems(
+                Update,
+                apply_hold_to_joint
+                    .after(adjust_hold_distance)
+                    .after(rotate_held_object)
+                    .after(cycle_rotation_snap),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct HoldSettings {
+    /// Change of the hold offset per scroll wheel line.
+    pub scroll_step: f32,
+    pub min_offset: f32,
+    pub max_offset: f32,
+    /// Radians of object rotation per pixel of mouse motion.
+    pub rotation_sensitivity: f32,
+}
+
+impl Default for HoldSettings {
+    fn default() -> Self {
+        Self {
+            scroll_step: 0.25,
+            min_offset: -1.0,
+            max_offset: 4.0,
+            rotation_sensitivity: 0.005,
+        }
+    }
+}
+
+/// Increments held objects snap their rotation to.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationSnap {
+    #[default]
+    Off,
+    Degrees15,
+    Degrees45,
+    Degrees90,
+}
+
+impl RotationSnap {
+    pub fn step(&self) -> Option<f32> {
+        match self {
+            RotationSnap::Off => None,
+            RotationSnap::Degrees15 => Some(TAU / 24.0),
+            RotationSnap::Degrees45 => Some(TAU / 8.0),
+            RotationSnap::Degrees90 => Some(TAU / 4.0),
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            RotationSnap::Off => RotationSnap::Degrees15,
+            RotationSnap::Degrees15 => RotationSnap::Degrees45,
+            RotationSnap::Degrees45 => RotationSnap::Degrees90,
+            RotationSnap::Degrees90 => RotationSnap::Off,
+        }
+    }
+
+    /// Rounds every Euler angle of `rotation` to the nearest increment.
+    pub fn apply(&self, rotation: Quat) -> Quat {
+        let Some(step) = self.step() else {
+            return rotation;
+        };
+        let (y, x, z) = rotation.to_euler(EulerRot::YXZ);
+        let snap = |angle: f32| (angle / step).round() * step;
+        Quat::from_euler(EulerRot::YXZ, snap(y), snap(x), snap(z))
+    }
+}
+
+/// Anchor and basis of the fixed joint between the hand and the object it holds.
+///
+/// Both are on the held object's side of the joint, its first body, and place the object
+/// `hold_offset` in front of the hand whatever its rotation.
+pub fn hold_frame(grabber: &Grabber, snap: RotationSnap) -> (Vec3, Quat) {
+    // The object faces the camera when grabbed, hence the half turn.
+    let basis = snap.apply(grabber.hold_rotation) * Quat::from_rotation_y(TAU * 0.5);
+    // The anchor is in the object's frame, which is turned by the inverse of the basis
+    // relative to the hand.
+    (basis * (Vec3::Z * grabber.hold_offset), basis)
+}
+
+fn cycle_rotation_snap(key: Res<Input<KeyCode>>, mut rotation_snap: ResMut<RotationSnap>) {
+    if key.just_pressed(ROTATION_SNAP_KEY) {
+        *rotation_snap = rotation_snap.next();
+        info!("rotation snap: {:?}", *rotation_snap);
+    }
+}
+
+fn adjust_hold_distance(
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    settings: Res<HoldSettings>,
+    mut grabber_query: Query<&mut Grabber, With<RightHand>>,
+) {
+    let Ok(mut grabber) = grabber_query.get_single_mut() else {
+        return;
+    };
+    for mouse_wheel in mouse_wheel_events.iter() {
+        if grabber.grabbed_entity.is_none() {
+            continue;
+        }
+        let lines = match mouse_wheel.unit {
+            MouseScrollUnit::Line => mouse_wheel.y,
+            MouseScrollUnit::Pixel => mouse_wheel.y / 20.0,
+        };
+        grabber.hold_offset = (grabber.hold_offset + lines * settings.scroll_step)
+            .clamp(settings.min_offset, settings.max_offset);
+    }
+}
+
+/// Whether the right hand is holding something and the rotate key is down.
+fn rotating_held_object(
+    key: &Input<KeyCode>,
+    active_tool: ActiveTool,
+    grabber_query: &Query<&mut Grabber, With<RightHand>>,
+) -> bool {
+    active_tool == ActiveTool::Hand
+        && key.pressed(ROTATE_KEY)
+        && grabber_query
+            .get_single()
+            .map_or(false, |grabber| grabber.grabbed_entity.is_some())
+}
+
+/// Suspends the look input while the held object is rotated.
+///
+/// Runs regardless of the active tool, so switching tools or dropping the object with the
+/// rotate key still down gives the look input back.
+fn suspend_look_input(
+    key: Res<Input<KeyCode>>,
+    active_tool: Res<ActiveTool>,
+    grabber_query: Query<&mut Grabber, With<RightHand>>,
+    mut controller_query: Query<&mut FpsController>,
+    mut suspended_sensitivity: Local<Option<f32>>,
+) {
+    let rotating = rotating_held_object(&key, *active_tool, &grabber_query);
+
+    // Zero sensitivity keeps movement working while the look input is suspended.
+    for mut controller in controller_query.iter_mut() {
+        match (rotating, *suspended_sensitivity) {
+            (true, None) => {
+                *suspended_sensitivity = Some(controller.sensitivity);
+                controller.sensitivity = 0.0;
+            }
+            (false, Some(sensitivity)) => controller.sensitivity = sensitivity,
+            _ => {}
+        }
+    }
+    if !rotating {
+        *suspended_sensitivity = None;
+    }
+}
+
+/// While the rotate key is held, mouse motion turns the held object instead of the camera.
+fn rotate_held_object(
+    key: Res<Input<KeyCode>>,
+    active_tool: Res<ActiveTool>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    settings: Res<HoldSettings>,
+    mut grabber_query: Query<&mut Grabber, With<RightHand>>,
+) {
+    if !rotating_held_object(&key, *active_tool, &grabber_query) {
+        mouse_motion_events.clear();
+        return;
+    }
+    let Ok(mut grabber) = grabber_query.get_single_mut() else {
+        return;
+    };
+    for mouse_motion in mouse_motion_events.iter() {
+        let delta = mouse_motion.delta * settings.rotation_sensitivity;
+        grabber.hold_rotation = (Quat::from_rotation_y(delta.x)
+            * Quat::from_rotation_x(delta.y)
+            * grabber.hold_rotation)
+            .normalize();
+    }
+}
+
+fn apply_hold_to_joint(
+    rotation_snap: Res<RotationSnap>,
+    mut hand_query: Query<(&Grabber, &mut ImpulseJoint), With<RightHand>>,
+) {
+    let Ok((grabber, mut joint)) = hand_query.get_single_mut() else {
+        return;
+    };
+    if grabber.grabbed_entity.is_none() {
+        return;
+    }
+    let (anchor, basis) = hold_frame(grabber, *rotation_snap);
+    if joint.data.local_anchor1() != anchor || joint.data.local_basis1() != basis {
+        joint.data.set_local_anchor1(anchor).set_local_basis1(basis);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Position of the held object relative to a hand at the origin, from the joint frame.
+    fn held_position(grabber: &Grabber) -> Vec3 {
+        let (anchor, basis) = hold_frame(grabber, RotationSnap::Off);
+        // The object's frame at the anchor lines up with the hand's frame at its origin.
+        let object_rotation = basis.inverse();
+        -(object_rotation * anchor)
+    }
+
+    #[test]
+    fn rotated_object_stays_on_the_forward_axis() {
+        for hold_rotation in [
+            Quat::IDENTITY,
+            Quat::from_rotation_x(0.7),
+            Quat::from_euler(EulerRot::YXZ, 1.2, -0.4, 2.1),
+        ] {
+            let grabber = Grabber {
+                hold_offset: 1.5,
+                hold_rotation,
+                ..default()
+            };
+            let position = held_position(&grabber);
+            assert!(
+                position.abs_diff_eq(Vec3::NEG_Z * 1.5, 1e-5),
+                "{:?} held at {:?}",
+                hold_rotation,
+                position
+            );
+        }
+    }
+}
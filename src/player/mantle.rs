@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use bevy_fps_controller::controller::{
+    fps_controller_move, FpsController, FpsControllerInput, LogicalPlayer,
+};
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Lets the player climb onto [`Climbable`] ledges in front of them by pressing jump.
+pub struct MantlePlugin;
+
+impl Plugin for MantlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MantleSettings>()
+            .register_type::<MantleSettings>()
+            .register_type::<Mantling>()
+            .add_systems(Update, detect_ledges.before(fps_controller_move))
+            .add_systems(
+                Update,
+                animate_mantle
+                    .after(fps_controller_move)
+                    .before(PhysicsSet::SyncBackend),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct MantleSettings {
+    /// Ledges lower than this (above the feet) are left to the regular jump.
+    pub min_ledge_height: f32,
+    pub max_ledge_height: f32,
+    /// How far in front of the capsule ledges are searched.
+    pub reach: f32,
+    /// Duration of the whole climb in seconds.
+    pub duration: f32,
+}
+
+impl Default for MantleSettings {
+    fn default() -> Self {
+        Self {
+            min_ledge_height: 0.5,
+            max_ledge_height: 1.8,
+            reach: 0.5,
+            duration: 0.45,
+        }
+    }
+}
+
+/// Marks a surface the player can mantle onto.
+#[derive(Component, Schematic, Reflect, Debug, Default)]
+#[reflect(Schematic)]
+pub struct Climbable;
+
+/// A climb in progress, the player is moved up to the ledge and then over it.
+#[derive(Component, Reflect, Debug)]
+pub struct Mantling {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub elapsed: f32,
+}
+
+impl Mantling {
+    /// Position along the path after `progress` (0 to 1): first straight up, then forward.
+    fn position(&self, progress: f32) -> Vec3 {
+        let corner = Vec3::new(self.start.x, self.end.y, self.start.z);
+        let up = (self.end.y - self.start.y).max(0.0);
+        let over = self.end.distance(corner);
+        let split = if up + over > 0.0 {
+            up / (up + over)
+        } else {
+            1.0
+        };
+        if progress < split {
+            self.start.lerp(corner, progress / split)
+        } else {
+            corner.lerp(
+                self.end,
+                (progress - split) / (1.0 - split).max(f32::EPSILON),
+            )
+        }
+    }
+}
+
+/// Finds a climbable ledge in front of the player and starts mantling onto it on jump.
+fn detect_ledges(
+    mut commands: Commands,
+    key: Res<Input<KeyCode>>,
+    settings: Res<MantleSettings>,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &Collider,
+            &FpsController,
+            &FpsControllerInput,
+        ),
+        (With<LogicalPlayer>, Without<Mantling>),
+    >,
+    climbable_query: Query<(), With<Climbable>>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, transform, collider, controller, input) in player_query.iter() {
+        let aabb = collider.raw.compute_local_aabb();
+        let feet = transform.translation + Vec3::Y * aabb.mins.y;
+        let radius = (aabb.maxs.x - aabb.mins.x) * 0.5;
+        let forward = Quat::from_rotation_y(input.yaw) * Vec3::NEG_Z;
+        let filter = QueryFilter::default()
+            .exclude_sensors()
+            .exclude_rigid_body(entity);
+
+        // Drop a probe onto the area in front of the capsule to find the top of a ledge.
+        let probe_radius = radius * 0.8;
+        let probe = Collider::ball(probe_radius);
+        let probe_start = feet
+            + forward * (radius + settings.reach)
+            + Vec3::Y * (settings.max_ledge_height + probe_radius);
+        let Some((ledge, toi)) = rapier_context.cast_shape(
+            probe_start,
+            Quat::IDENTITY,
+            Vec3::NEG_Y,
+            &probe,
+            settings.max_ledge_height - settings.min_ledge_height,
+            filter,
+        ) else {
+            continue;
+        };
+        // A probe that starts inside geometry means the wall is taller than we can reach.
+        if toi.toi <= 0.0 {
+            continue;
+        }
+        let ledge_body = rapier_context.collider_parent(ledge).unwrap_or(ledge);
+        if climbable_query.get(ledge).is_err() && climbable_query.get(ledge_body).is_err() {
+            continue;
+        }
+        let ledge_top = probe_start.y - toi.toi - probe_radius;
+
+        // The capsule has to fit both above its current position and on top of the ledge.
+        let lift = ledge_top - feet.y + 0.02;
+        let raised = transform.translation + Vec3::Y * lift;
+        let end = raised + forward * (radius + settings.reach);
+        let blocked = [raised, end].into_iter().any(|position| {
+            rapier_context
+                .intersection_with_shape(position, transform.rotation, collider, filter)
+                .is_some()
+        });
+        if blocked {
+            continue;
+        }
+
+        gizmos.circle(
+            Vec3::new(end.x, ledge_top + 0.01, end.z),
+            Vec3::Y,
+            radius,
+            Color::YELLOW,
+        );
+        if key.just_pressed(controller.key_jump) {
+            commands.entity(entity).insert(Mantling {
+                start: transform.translation,
+                end,
+                elapsed: 0.0,
+            });
+        }
+    }
+}
+
+/// Drives the player along the mantle path by overriding the controller's velocity.
+pub fn animate_mantle(
+    mut commands: Commands,
+    settings: Res<MantleSettings>,
+    time: Res<Time>,
+    mut player_query: Query<(Entity, &Transform, &mut Velocity, &mut Mantling)>,
+) {
+    let delta_seconds = time.delta_seconds();
+    if delta_seconds <= 0.0 {
+        return;
+    }
+    for (entity, transform, mut velocity, mut mantling) in player_query.iter_mut() {
+        mantling.elapsed += delta_seconds;
+        let progress = (mantling.elapsed / settings.duration).min(1.0);
+        let target = mantling.position(progress);
+        velocity.linvel = (target - transform.translation) / delta_seconds;
+        velocity.angvel = Vec3::ZERO;
+        if progress >= 1.0 {
+            commands.entity(entity).remove::<Mantling>();
+        }
+    }
+}
@@ -1,5 +1,14 @@
+pub mod camera_rig;
+pub mod gravity_gun;
+pub mod hold_controls;
+pub mod mantle;
+pub mod movement_modes;
+pub mod placement;
+pub mod platforms;
 pub mod player_components;
 pub mod player_systems;
+pub mod pushing;
+pub mod stance;
 
 use crate::Player;
 use bevy::prelude::*;
@@ -9,48 +18,83 @@ use bevy_fps_controller::controller::{FpsController, FpsControllerInput, Logical
 use bevy_rapier3d::prelude::*;
 use std::f32::consts::TAU;
 
+use crate::damage::{Damageable, Dead, DeathBehavior, DeathEvent, FallTracker, Health};
 use crate::MainCamera;
 
+use self::camera_rig::{aim_point, camera_not_orbiting, CameraRigPlugin, ViewCamera};
+use self::gravity_gun::{ActiveTool, GravityGun, GravityGunPlugin};
+use self::hold_controls::{hold_frame, HoldControlsPlugin, RotationSnap};
+use self::mantle::MantlePlugin;
+use self::movement_modes::{MovementMode, MovementModesPlugin};
+use self::placement::PlacementPlugin;
+use self::platforms::{PlatformRider, PlatformsPlugin};
 use self::player_components::{Grabbable, Grabber, PIDController, RightHand};
+use self::pushing::PushingPlugin;
+use self::stance::{stance_camera, PlayerStance, StancePlugin};
 
 pub struct LocomotionPlugin;
 
 const SPAWN_POINT: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+const PLAYER_HEALTH: f32 = 100.0;
 
 impl Plugin for LocomotionPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, setup)
             .add_systems(Update, respawn)
+            .add_systems(Update, respawn_dead)
             .add_systems(Update, manage_cursor)
             .add_systems(PostUpdate, draw_crossair)
             .add_systems(Update, grabber_target_checking_system)
-            .add_systems(Update, grabbing_system)
+            .add_systems(
+                Update,
+                grabbing_system
+                    .run_if(resource_equals(ActiveTool::Hand))
+                    .run_if(camera_not_orbiting),
+            )
             .add_systems(
                 Update,
                 right_hand_placement_system
                     .after(fps_controller_render)
+                    .after(stance_camera)
                     .before(PhysicsSet::SyncBackend), // .in_set(RapierTransformPropagateSet),
             )
             .register_type::<Grabber>()
-            .register_type::<PIDController>();
+            .register_type::<PIDController>()
+            .add_plugins(GravityGunPlugin)
+            .add_plugins(HoldControlsPlugin)
+            .add_plugins(PlacementPlugin)
+            .add_plugins(MantlePlugin)
+            .add_plugins(PlatformsPlugin)
+            .add_plugins(PushingPlugin)
+            .add_plugins(StancePlugin)
+            .add_plugins(MovementModesPlugin)
+            .add_plugins(CameraRigPlugin);
     }
 }
 
-fn draw_crossair(mut gizmos: Gizmos, camera_transform_query: Query<&Transform, With<MainCamera>>) {
-    if camera_transform_query.get_single().is_err() {
+/// Draws the crosshair in front of the view, over the point the player's eye aims at.
+fn draw_crossair(
+    mut gizmos: Gizmos,
+    rapier_context: Res<RapierContext>,
+    camera_transform_query: Query<&Transform, With<MainCamera>>,
+    view_query: Query<&Transform, With<ViewCamera>>,
+    player_query: Query<Entity, With<LogicalPlayer>>,
+) {
+    let (Ok(eye), Ok(t)) = (camera_transform_query.get_single(), view_query.get_single()) else {
         return;
     };
     let corsair_size = 0.01;
     let color = Color::WHITE;
-    let t = camera_transform_query.get_single().unwrap();
-    let start = t.translation + t.forward() - t.right() * corsair_size;
+    let target = aim_point(&rapier_context, eye, player_query.get_single().ok());
+    let center = t.translation + (target - t.translation).normalize_or_zero();
+    let start = center - t.right() * corsair_size;
     // let start = Vec3::ZERO;
-    let end = t.translation + t.forward() + t.right() * corsair_size;
+    let end = center + t.right() * corsair_size;
 
     gizmos.line(start, end, color);
 
-    let top = t.translation + t.forward() + t.up() * corsair_size;
-    let bottom = t.translation + t.forward() - t.up() * corsair_size;
+    let top = center + t.up() * corsair_size;
+    let bottom = center - t.up() * corsair_size;
     gizmos.line(top, bottom, color);
 }
 
@@ -103,6 +147,15 @@ fn setup(
             },
             Name::new("Player"),
             Player,
+            Health::new(PLAYER_HEALTH),
+            Damageable {
+                death: DeathBehavior::Respawn,
+                ..default()
+            },
+            FallTracker::default(),
+            PlatformRider::default(),
+            PlayerStance::default(),
+            MovementMode::default(),
         ));
 
     commands.spawn((
@@ -127,6 +180,7 @@ fn setup(
             grabbing_speed: 1000.0,
             ..default()
         },
+        GravityGun::default(),
         RigidBody::KinematicPositionBased,
     ));
 }
@@ -154,11 +208,34 @@ fn respawn(mut query: Query<(&mut Transform, &mut Velocity)>) {
             continue;
         }
 
-        velocity.linvel = Vec3::ZERO;
-        transform.translation = SPAWN_POINT;
+        reset_to_spawn(&mut transform, &mut velocity);
+    }
+}
+
+/// Brings entities that died with [`DeathBehavior::Respawn`] back at the spawn point.
+fn respawn_dead(
+    mut commands: Commands,
+    mut death_events: EventReader<DeathEvent>,
+    mut query: Query<(&mut Transform, &mut Velocity, &mut Health)>,
+) {
+    for death in death_events.iter() {
+        if death.behavior != DeathBehavior::Respawn {
+            continue;
+        }
+        let Ok((mut transform, mut velocity, mut health)) = query.get_mut(death.entity) else {
+            continue;
+        };
+        reset_to_spawn(&mut transform, &mut velocity);
+        health.current = health.max;
+        commands.entity(death.entity).remove::<Dead>();
     }
 }
 
+fn reset_to_spawn(transform: &mut Transform, velocity: &mut Velocity) {
+    velocity.linvel = Vec3::ZERO;
+    transform.translation = SPAWN_POINT;
+}
+
 fn manage_cursor(
     // btn: Res<Input<MouseButton>>,
     key: Res<Input<KeyCode>>,
@@ -242,7 +319,7 @@ pub fn grabbing_system(
     mut commands: Commands,
     mouse: Res<Input<MouseButton>>,
     mut grabber_query: Query<(Entity, &mut Grabber, &GlobalTransform)>,
-    mut grabbable_query: Query<(&mut Velocity, &Transform, &mut PIDController), With<Grabbable>>,
+    mut grabbable_query: Query<(&mut Velocity, &Transform, &mut PIDController, &Grabbable)>,
     rigidbody_query: Query<&RapierRigidBodyHandle>,
     time: Res<Time>,
     // player_query: Query<Entity, With<MainCamera>>,
@@ -253,12 +330,13 @@ pub fn grabbing_system(
     // }
     for (grabber_entity, mut grabber, grabber_transform) in grabber_query.iter_mut() {
         if grabber.grabbed_entity.is_some() {
-            let rb = rigidbody_query
-                .get(grabber.grabbed_entity.unwrap())
-                .unwrap();
-            rapier_context.bodies.get_mut(rb.0).unwrap().wake_up(true);
+            let rb = rigidbody_query.get(grabber.grabbed_entity.unwrap());
+            if let Ok(rb) = rb {
+                rapier_context.bodies.get_mut(rb.0).unwrap().wake_up(true);
+            }
 
-            if mouse.pressed(MouseButton::Right) {
+            // Let go when released or when the held object no longer exists (e.g. it broke).
+            if mouse.pressed(MouseButton::Right) || rb.is_err() {
                 commands
                     .get_entity(grabber_entity)
                     .unwrap()
@@ -290,7 +368,11 @@ pub fn grabbing_system(
         if grabbable.is_err() {
             continue;
         }
-        let (mut velocity, transform, mut pid) = grabbable.unwrap();
+        let (mut velocity, transform, mut pid, grabbable) = grabbable.unwrap();
+        // Nothing to attract while the simulation is paused, the PID can't work with no time.
+        if time.delta_seconds() <= 0.0 {
+            continue;
+        }
 
         let direction = grabber_transform.translation() - transform.translation;
         velocity.linvel = direction.normalize() * time.delta_seconds() * grabber.grabbing_speed;
@@ -300,11 +382,14 @@ pub fn grabbing_system(
         let angular_velocity_correction = pid.update(angular_velocity_error, time.delta_seconds());
 
         velocity.angvel = angular_velocity_correction;
-        if direction.length() < 1.0 {
-            let joint = FixedJointBuilder::new()
-                .local_anchor1(Vec3::ZERO)
-                .local_basis1(Quat::from_rotation_y(TAU * 0.5));
+        if direction.length() < grabbable.snap_distance {
             grabber.grabbed_entity = Some(grabbable_entity);
+            grabber.hold_offset = 0.0;
+            grabber.hold_rotation = Quat::IDENTITY;
+            let (anchor, basis) = hold_frame(&grabber, RotationSnap::Off);
+            let joint = FixedJointBuilder::new()
+                .local_anchor1(anchor)
+                .local_basis1(basis);
             commands
                 .get_entity(grabber_entity)
                 .unwrap()
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use bevy_fps_controller::controller::{
+    fps_controller_input, fps_controller_move, FpsController, FpsControllerInput, LogicalPlayer,
+    MoveMode,
+};
+use bevy_rapier3d::prelude::*;
+
+use super::player_components::PlayerInput;
+
+const FLY_SPEED_UP_KEY: KeyCode = KeyCode::Equals;
+const FLY_SPEED_DOWN_KEY: KeyCode = KeyCode::Minus;
+const FLY_SPEED_STEP: f32 = 1.25;
+const MIN_FLY_SPEED: f32 = 1.0;
+const MAX_FLY_SPEED: f32 = 100.0;
+
+/// Debug movement modes to look at physics setups from anywhere.
+pub struct MovementModesPlugin;
+
+impl Plugin for MovementModesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MovementMode>().add_systems(
+            Update,
+            switch_movement_mode
+                .after(fps_controller_input)
+                .before(fps_controller_move),
+        );
+    }
+}
+
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MovementMode {
+    /// Regular walking with gravity.
+    #[default]
+    Walk,
+    /// Free flight without gravity, still colliding with the world.
+    Fly,
+    /// Free flight through everything, the collider is disabled.
+    Noclip,
+}
+
+impl MovementMode {
+    fn next(&self) -> Self {
+        match self {
+            MovementMode::Walk => MovementMode::Fly,
+            MovementMode::Fly => MovementMode::Noclip,
+            MovementMode::Noclip => MovementMode::Walk,
+        }
+    }
+}
+
+/// Cycles the [`MovementMode`] with the controller's fly key and adjusts the fly speed.
+///
+/// The controller would toggle between walking and flying on its own, so its fly
+/// input is consumed here before it moves the player.
+fn switch_movement_mode(
+    mut commands: Commands,
+    key: Res<Input<KeyCode>>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut MovementMode,
+            &mut FpsController,
+            &mut FpsControllerInput,
+            &mut Velocity,
+        ),
+        With<LogicalPlayer>,
+    >,
+    mut input_query: Query<&mut PlayerInput>,
+) {
+    for (entity, mut movement_mode, mut controller, mut input, mut velocity) in
+        player_query.iter_mut()
+    {
+        if key.just_pressed(FLY_SPEED_UP_KEY) {
+            controller.fly_speed = (controller.fly_speed * FLY_SPEED_STEP).min(MAX_FLY_SPEED);
+        }
+        if key.just_pressed(FLY_SPEED_DOWN_KEY) {
+            controller.fly_speed = (controller.fly_speed / FLY_SPEED_STEP).max(MIN_FLY_SPEED);
+        }
+
+        if !input.fly {
+            continue;
+        }
+        input.fly = false;
+        *movement_mode = movement_mode.next();
+        info!("movement mode: {:?}", *movement_mode);
+
+        controller.move_mode = match *movement_mode {
+            MovementMode::Walk => MoveMode::Ground,
+            MovementMode::Fly | MovementMode::Noclip => MoveMode::Noclip,
+        };
+        velocity.linvel = Vec3::ZERO;
+        if *movement_mode == MovementMode::Noclip {
+            commands.entity(entity).insert(ColliderDisabled);
+        } else {
+            commands.entity(entity).remove::<ColliderDisabled>();
+        }
+        for mut player_input in input_query.iter_mut() {
+            player_input.fly = *movement_mode != MovementMode::Walk;
+        }
+    }
+}
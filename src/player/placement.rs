@@ -0,0 +1,294 @@
+use bevy::prelude::*;
+use bevy_fps_controller::controller::LogicalPlayer;
+use bevy_rapier3d::prelude::*;
+
+use super::camera_rig::camera_not_orbiting;
+use super::gravity_gun::ActiveTool;
+use super::player_components::{Grabber, RightHand};
+use crate::MainCamera;
+
+const PLACEMENT_TOGGLE_KEY: KeyCode = KeyCode::P;
+/// Places the held object as a sleeping dynamic body.
+const PLACE_BUTTON: MouseButton = MouseButton::Left;
+/// Places the held object frozen as a fixed body.
+const PLACE_FIXED_BUTTON: MouseButton = MouseButton::Middle;
+const MAX_PLACEMENT_DISTANCE: f32 = 15.0;
+/// Height above the surface the overlap shape-cast starts from.
+const CAST_HEIGHT: f32 = 0.5;
+/// Penetration tolerated between the placed object and the surface it rests on.
+const SURFACE_TOLERANCE: f32 = 0.02;
+
+/// Precise placement of the held object on surfaces, previewed before it is let go.
+pub struct PlacementPlugin;
+
+impl Plugin for PlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlacementMode>()
+            .add_systems(Update, toggle_placement_mode)
+            .add_systems(
+                Update,
+                update_placement
+                    .after(toggle_placement_mode)
+                    .run_if(resource_equals(ActiveTool::Hand))
+                    .run_if(camera_not_orbiting),
+            )
+            .add_systems(Update, sync_placement_preview.after(update_placement));
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct PlacementMode {
+    pub active: bool,
+    /// Where the held object would be placed, if it fits there.
+    pub target: Option<PlacementTarget>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PlacementTarget {
+    pub transform: Transform,
+    /// Whether the object fits without overlapping anything.
+    pub valid: bool,
+}
+
+/// Run condition for systems that use the mouse buttons placement takes over.
+pub fn placement_inactive(placement_mode: Res<PlacementMode>) -> bool {
+    !placement_mode.active
+}
+
+/// Translucent copy of the held object's bounds shown at the placement target.
+#[derive(Component)]
+struct PlacementPreview {
+    material: Handle<StandardMaterial>,
+}
+
+fn toggle_placement_mode(key: Res<Input<KeyCode>>, mut placement_mode: ResMut<PlacementMode>) {
+    if key.just_pressed(PLACEMENT_TOGGLE_KEY) {
+        placement_mode.active = !placement_mode.active;
+        placement_mode.target = None;
+    }
+}
+
+fn update_placement(
+    mut commands: Commands,
+    mouse: Res<Input<MouseButton>>,
+    mut placement_mode: ResMut<PlacementMode>,
+    rapier_context: Res<RapierContext>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+    player_query: Query<Entity, With<LogicalPlayer>>,
+    mut hand_query: Query<(Entity, &mut Grabber), With<RightHand>>,
+    part_query: BodyPartQuery,
+    body_query: Query<(), With<RigidBody>>,
+    mut armed: Local<Option<Entity>>,
+) {
+    placement_mode.target = None;
+    let held = hand_query
+        .get_single()
+        .ok()
+        .and_then(|(_, grabber)| grabber.grabbed_entity);
+    // Only a press made while already holding the object places it on release, not the
+    // release of the press that grabbed it.
+    if held.is_none() || *armed != held {
+        *armed = None;
+    }
+    if held.is_some() && mouse.just_pressed(PLACE_BUTTON) {
+        *armed = held;
+    }
+    if !placement_mode.active {
+        return;
+    }
+    let (Some(held), Ok((hand, mut grabber))) = (held, hand_query.get_single_mut()) else {
+        return;
+    };
+    let (Ok(camera), Ok((held_transform, ..)), Some(collider)) = (
+        camera_query.get_single(),
+        part_query.get(held),
+        body_collider(held, &part_query, &body_query),
+    ) else {
+        return;
+    };
+
+    let mut filter = QueryFilter::default()
+        .exclude_sensors()
+        .exclude_rigid_body(held);
+    if let Ok(player) = player_query.get_single() {
+        filter = filter.exclude_rigid_body(player);
+    }
+    let Some((_, hit)) = rapier_context.cast_ray_and_get_normal(
+        camera.translation,
+        camera.forward(),
+        MAX_PLACEMENT_DISTANCE,
+        true,
+        filter,
+    ) else {
+        return;
+    };
+
+    // Stand the object up along the surface normal, keeping its heading.
+    let (yaw, _, _) = held_transform.rotation.to_euler(EulerRot::YXZ);
+    let rotation = Quat::from_rotation_arc(Vec3::Y, hit.normal) * Quat::from_rotation_y(yaw);
+
+    // Lower the collider onto the surface, anything hit before the surface is in the way.
+    let bottom = -collider.raw.compute_local_aabb().mins.y;
+    let resting = hit.point + hit.normal * bottom;
+    let start = resting + hit.normal * CAST_HEIGHT;
+    let (translation, valid) = match rapier_context.cast_shape(
+        start,
+        rotation,
+        -hit.normal,
+        &collider,
+        CAST_HEIGHT + SURFACE_TOLERANCE,
+        filter,
+    ) {
+        Some((_, toi)) => (
+            start - hit.normal * toi.toi,
+            toi.toi > 0.0 && toi.toi >= CAST_HEIGHT - SURFACE_TOLERANCE,
+        ),
+        None => (resting, true),
+    };
+    let transform = Transform::from_translation(translation).with_rotation(rotation);
+    placement_mode.target = Some(PlacementTarget { transform, valid });
+
+    let fixed = mouse.just_pressed(PLACE_FIXED_BUTTON);
+    let released = *armed == Some(held) && mouse.just_released(PLACE_BUTTON);
+    if !valid || !(fixed || released) {
+        return;
+    }
+    *armed = None;
+    commands.entity(hand).remove::<ImpulseJoint>();
+    grabber.grabbed_entity = None;
+    grabber.attracted_target = None;
+
+    let mut held_commands = commands.entity(held);
+    held_commands.insert((
+        transform,
+        Velocity::zero(),
+        Sleeping {
+            sleeping: true,
+            ..default()
+        },
+    ));
+    if fixed {
+        held_commands.insert(RigidBody::Fixed);
+    }
+}
+
+/// Local transform, collider and children of the entities making up a body.
+type BodyPartQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        Option<&'static Collider>,
+        Option<&'static Children>,
+    ),
+    Without<PlacementPreview>,
+>;
+
+/// Shape of `body` in its own space, made of its collider and those of its children.
+///
+/// Children with a rigid body of their own are separate bodies and left out.
+fn body_collider(
+    body: Entity,
+    part_query: &BodyPartQuery,
+    body_query: &Query<(), With<RigidBody>>,
+) -> Option<Collider> {
+    let mut shapes = Vec::new();
+    let mut pending = vec![(body, Transform::IDENTITY)];
+    while let Some((entity, to_body)) = pending.pop() {
+        let Ok((_, collider, children)) = part_query.get(entity) else {
+            continue;
+        };
+        if let Some(collider) = collider {
+            shapes.push((to_body.translation, to_body.rotation, collider.clone()));
+        }
+        for child in children.into_iter().flatten() {
+            if body_query.contains(*child) {
+                continue;
+            }
+            if let Ok((local, ..)) = part_query.get(*child) {
+                pending.push((*child, to_body * *local));
+            }
+        }
+    }
+    match shapes.len() {
+        0 => None,
+        1 if shapes[0].0 == Vec3::ZERO && shapes[0].1 == Quat::IDENTITY => {
+            shapes.pop().map(|(_, _, collider)| collider)
+        }
+        _ => Some(Collider::compound(shapes)),
+    }
+}
+
+fn sync_placement_preview(
+    mut commands: Commands,
+    placement_mode: Res<PlacementMode>,
+    hand_query: Query<&Grabber, With<RightHand>>,
+    part_query: BodyPartQuery,
+    body_query: Query<(), With<RigidBody>>,
+    mut preview_query: Query<(Entity, &PlacementPreview, &mut Transform, &mut Visibility)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut previewed: Local<Option<Entity>>,
+) {
+    let held = hand_query
+        .get_single()
+        .ok()
+        .and_then(|grabber| grabber.grabbed_entity)
+        .filter(|_| placement_mode.active);
+
+    // Rebuild the preview whenever a different object is held.
+    if *previewed != held {
+        for (entity, _, _, _) in preview_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        *previewed = held;
+        let Some(collider) = held.and_then(|held| body_collider(held, &part_query, &body_query))
+        else {
+            return;
+        };
+        let aabb = collider.raw.compute_local_aabb();
+        let min: Vec3 = aabb.mins.into();
+        let max: Vec3 = aabb.maxs.into();
+        let material = materials.add(StandardMaterial {
+            base_color: Color::rgba(0.2, 1.0, 0.2, 0.35),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        commands
+            .spawn((
+                SpatialBundle {
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                PlacementPreview {
+                    material: material.clone(),
+                },
+                Name::new("placement_preview"),
+            ))
+            .with_children(|commands| {
+                commands.spawn(PbrBundle {
+                    mesh: meshes.add(shape::Box::from_corners(min, max).into()),
+                    material,
+                    ..default()
+                });
+            });
+        return;
+    }
+
+    for (_, preview, mut transform, mut visibility) in preview_query.iter_mut() {
+        let Some(target) = placement_mode.target else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Visible;
+        *transform = target.transform;
+        if let Some(material) = materials.get_mut(&preview.material) {
+            material.base_color = if target.valid {
+                Color::rgba(0.2, 1.0, 0.2, 0.35)
+            } else {
+                Color::rgba(1.0, 0.2, 0.2, 0.35)
+            };
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use bevy_fps_controller::controller::{
+    fps_controller_move, FpsController, FpsControllerInput, LogicalPlayer,
+};
+use bevy_rapier3d::prelude::*;
+
+use super::mantle::{animate_mantle, Mantling};
+use super::movement_modes::MovementMode;
+
+/// How far below the feet the ground is searched for.
+const GROUND_PROBE_DISTANCE: f32 = 0.15;
+
+/// Makes the player move along with the body they stand on and weigh down dynamic ones.
+pub struct PlatformsPlugin;
+
+impl Plugin for PlatformsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PlatformRider>()
+            .add_systems(Update, remove_platform_velocity.before(fps_controller_move))
+            .add_systems(
+                Update,
+                ride_platforms
+                    .after(fps_controller_move)
+                    .after(animate_mantle)
+                    .before(PhysicsSet::SyncBackend),
+            );
+    }
+}
+
+/// The body the player stands on and the velocity it lent them this frame.
+#[derive(Component, Reflect, Debug, Default)]
+pub struct PlatformRider {
+    pub platform: Option<Entity>,
+    /// Platform velocity added on top of the controller's velocity, which is taken
+    /// off again before the controller runs so it never brakes the platform motion.
+    pub inherited: Vec3,
+}
+
+fn remove_platform_velocity(mut rider_query: Query<(&mut Velocity, &mut PlatformRider)>) {
+    for (mut velocity, mut rider) in rider_query.iter_mut() {
+        velocity.linvel -= rider.inherited;
+        rider.inherited = Vec3::ZERO;
+    }
+}
+
+pub fn ride_platforms(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut rider_query: Query<
+        (
+            Entity,
+            &Transform,
+            &Collider,
+            &FpsController,
+            &mut FpsControllerInput,
+            &mut Velocity,
+            &mut PlatformRider,
+            &MovementMode,
+            Option<&RapierRigidBodyHandle>,
+        ),
+        (With<LogicalPlayer>, Without<Mantling>),
+    >,
+    mut platform_query: Query<(&RapierRigidBodyHandle, Option<&mut ExternalImpulse>)>,
+) {
+    let delta_seconds = time.delta_seconds();
+    for (
+        entity,
+        transform,
+        collider,
+        controller,
+        mut input,
+        mut velocity,
+        mut rider,
+        movement_mode,
+        rider_handle,
+    ) in rider_query.iter_mut()
+    {
+        rider.platform = None;
+        if *movement_mode != MovementMode::Walk {
+            continue;
+        }
+
+        let aabb = collider.raw.compute_local_aabb();
+        let radius = (aabb.maxs.x - aabb.mins.x) * 0.5;
+        let probe_radius = radius * 0.9;
+        let probe_start = transform.translation + Vec3::Y * (aabb.mins.y + radius);
+        let filter = QueryFilter::default()
+            .exclude_sensors()
+            .exclude_rigid_body(entity);
+        let Some((ground, toi)) = rapier_context.cast_shape(
+            probe_start,
+            Quat::IDENTITY,
+            Vec3::NEG_Y,
+            &Collider::ball(probe_radius),
+            radius - probe_radius + GROUND_PROBE_DISTANCE,
+            filter,
+        ) else {
+            continue;
+        };
+        let platform = rapier_context.collider_parent(ground).unwrap_or(ground);
+        let Ok((platform_handle, external_impulse)) = platform_query.get_mut(platform) else {
+            continue;
+        };
+        let Some(platform_body) = rapier_context.bodies.get(platform_handle.0) else {
+            continue;
+        };
+        if platform_body.is_fixed() {
+            continue;
+        }
+        rider.platform = Some(platform);
+
+        let contact = probe_start + Vec3::NEG_Y * (toi.toi + probe_radius);
+        let platform_velocity: Vec3 = platform_body.velocity_at_point(&contact.into()).into();
+        rider.inherited = platform_velocity;
+        velocity.linvel += platform_velocity;
+        // Turn with the platform as well.
+        input.yaw += platform_body.angvel().y * delta_seconds;
+
+        // Weigh down dynamic platforms with the rider's mass.
+        if !platform_body.is_dynamic() {
+            continue;
+        }
+        let mass = rider_handle
+            .and_then(|handle| rapier_context.bodies.get(handle.0))
+            .map_or(1.0, |body| body.mass());
+        let center_of_mass: Vec3 = (*platform_body.center_of_mass()).into();
+        let weight = ExternalImpulse::at_point(
+            Vec3::NEG_Y * mass * controller.gravity * delta_seconds,
+            contact,
+            center_of_mass,
+        );
+        match external_impulse {
+            Some(mut external_impulse) => {
+                external_impulse.impulse += weight.impulse;
+                external_impulse.torque_impulse += weight.torque_impulse;
+            }
+            None => {
+                commands.entity(platform).insert(weight);
+            }
+        }
+    }
+}
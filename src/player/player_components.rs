@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy_proto::prelude::*;
 
 #[derive(Component)]
 pub struct Player;
@@ -17,10 +18,25 @@ pub struct Grabber {
     pub potential_target: Option<Entity>,
     pub attracted_target: Option<Entity>,
     pub grabbed_entity: Option<Entity>,
+    /// How much further than the hand a held object is kept, along the view direction.
+    pub hold_offset: f32,
+    /// Rotation of a held object relative to its orientation when it was grabbed,
+    /// around the hand's axes.
+    pub hold_rotation: Quat,
 }
 
-#[derive(Component, Reflect)]
-pub struct Grabbable;
+#[derive(Component, Schematic, Reflect)]
+#[reflect(Schematic)]
+pub struct Grabbable {
+    /// Distance to the grabber below which the attracted object gets attached to it.
+    pub snap_distance: f32,
+}
+
+impl Default for Grabbable {
+    fn default() -> Self {
+        Self { snap_distance: 1.0 }
+    }
+}
 
 #[derive(Component, Default, Reflect)]
 pub struct PIDController {
@@ -29,6 +45,8 @@ pub struct PIDController {
     pub d_factor: f32,
     integral: Vec3,
     last_error: Vec3,
+    last_derivative: Vec3,
+    last_output: Vec3,
 }
 
 impl PIDController {
@@ -37,8 +55,7 @@ impl PIDController {
             p_factor,
             i_factor,
             d_factor,
-            integral: Vec3::ZERO,
-            last_error: Vec3::ZERO,
+            ..default()
         }
     }
 
@@ -46,9 +63,45 @@ impl PIDController {
         self.integral += current_error * delat_time;
         let derivative = (current_error - self.last_error) / delat_time;
         self.last_error = current_error;
-        return current_error * self.p_factor
+        self.last_derivative = derivative;
+        self.last_output = current_error * self.p_factor
             + self.integral * self.i_factor
             + derivative * self.d_factor;
+        return self.last_output;
+    }
+
+    /// Error of the last update.
+    pub fn error(&self) -> Vec3 {
+        self.last_error
+    }
+
+    pub fn integral(&self) -> Vec3 {
+        self.integral
+    }
+
+    /// Derivative of the error at the last update.
+    pub fn derivative(&self) -> Vec3 {
+        self.last_derivative
+    }
+
+    pub fn output(&self) -> Vec3 {
+        self.last_output
+    }
+}
+
+/// Prototype input for a [`PIDController`], only exposing its gains.
+#[derive(Schematic, Reflect)]
+#[reflect(Schematic)]
+#[schematic(into = PIDController)]
+pub struct PIDControllerProto {
+    pub p_factor: f32,
+    pub i_factor: f32,
+    pub d_factor: f32,
+}
+
+impl From<PIDControllerProto> for PIDController {
+    fn from(value: PIDControllerProto) -> Self {
+        PIDController::new(value.p_factor, value.i_factor, value.d_factor)
     }
 }
 
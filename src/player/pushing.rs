@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use bevy_fps_controller::controller::{fps_controller_move, LogicalPlayer};
+use bevy_rapier3d::prelude::*;
+
+use super::mantle::{animate_mantle, Mantling};
+use super::platforms::ride_platforms;
+use super::player_components::Grabber;
+
+/// Controlled pushing of dynamic bodies the player walks into.
+///
+/// The light player capsule barely moves props through the solver, so this applies a
+/// push force limited by [`PushSettings`] to what the player's own collider touches.
+/// Objects held by a [`Grabber`] are carried, not pushed.
+pub struct PushingPlugin;
+
+impl Plugin for PushingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PushSettings>()
+            .register_type::<PushSettings>()
+            .add_systems(
+                Update,
+                push_bodies
+                    .after(fps_controller_move)
+                    .after(animate_mantle)
+                    .before(ride_platforms),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct PushSettings {
+    /// Maximum force in N the player can push with.
+    pub push_strength: f32,
+    /// Pushed bodies are never accelerated beyond this speed, nor beyond the player's.
+    pub max_push_speed: f32,
+    /// How much faster than the pushed body the player may keep walking into it.
+    pub lead_speed: f32,
+    /// Contacts whose normal points up or down more than this are not pushes.
+    pub max_normal_y: f32,
+}
+
+impl Default for PushSettings {
+    fn default() -> Self {
+        Self {
+            push_strength: 120.0,
+            max_push_speed: 4.0,
+            lead_speed: 0.3,
+            max_normal_y: 0.7,
+        }
+    }
+}
+
+fn push_bodies(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<PushSettings>,
+    rapier_context: Res<RapierContext>,
+    mut player_query: Query<(Entity, &mut Velocity), (With<LogicalPlayer>, Without<Mantling>)>,
+    mut body_query: Query<
+        (
+            &RapierRigidBodyHandle,
+            &Velocity,
+            Option<&mut ExternalImpulse>,
+        ),
+        Without<LogicalPlayer>,
+    >,
+    grabber_query: Query<&Grabber>,
+) {
+    let delta_seconds = time.delta_seconds();
+    if delta_seconds <= 0.0 {
+        return;
+    }
+
+    for (player, mut player_velocity) in player_query.iter_mut() {
+        for contact_pair in rapier_context.contacts_with(player) {
+            if !contact_pair.has_any_active_contacts() {
+                continue;
+            }
+            let is_first = contact_pair.collider1() == player;
+            let other = if is_first {
+                contact_pair.collider2()
+            } else {
+                contact_pair.collider1()
+            };
+            let body = rapier_context.collider_parent(other).unwrap_or(other);
+            if grabber_query
+                .iter()
+                .any(|grabber| grabber.grabbed_entity == Some(body))
+            {
+                continue;
+            }
+            let Ok((handle, body_velocity, external_impulse)) = body_query.get_mut(body) else {
+                continue;
+            };
+            let Some(rapier_body) = rapier_context.bodies.get(handle.0) else {
+                continue;
+            };
+            if !rapier_body.is_dynamic() {
+                continue;
+            }
+
+            // Horizontal push direction and the average contact point.
+            let mut normal = Vec3::ZERO;
+            let mut point = Vec3::ZERO;
+            let mut points = 0;
+            for manifold in contact_pair.manifolds() {
+                // The manifold normal points from the first to the second collider.
+                normal += if is_first {
+                    manifold.normal()
+                } else {
+                    -manifold.normal()
+                };
+                for contact in manifold.solver_contacts() {
+                    point += contact.point();
+                    points += 1;
+                }
+            }
+            let normal = normal.normalize_or_zero();
+            if points == 0 || normal.y.abs() > settings.max_normal_y {
+                continue;
+            }
+            point /= points as f32;
+            let direction = Vec3::new(normal.x, 0.0, normal.z).normalize_or_zero();
+
+            let walking_speed = player_velocity.linvel.dot(direction);
+            if walking_speed <= 0.0 {
+                continue;
+            }
+            let body_speed = body_velocity.linvel.dot(direction);
+            let target_speed = walking_speed.min(settings.max_push_speed);
+
+            // Accelerate the body towards the walking speed, but never past it.
+            if body_speed < target_speed {
+                let mass = rapier_body.mass();
+                let force = (mass * (target_speed - body_speed) / delta_seconds)
+                    .min(settings.push_strength);
+                let center_of_mass: Vec3 = (*rapier_body.center_of_mass()).into();
+                let push = ExternalImpulse::at_point(
+                    direction * force * delta_seconds,
+                    point,
+                    center_of_mass,
+                );
+                match external_impulse {
+                    Some(mut external_impulse) => {
+                        external_impulse.impulse += push.impulse;
+                        external_impulse.torque_impulse += push.torque_impulse;
+                    }
+                    None => {
+                        commands.entity(body).insert(push);
+                    }
+                }
+            }
+
+            // Heavy bodies hold the player back to the speed they actually move at.
+            let allowed_speed = body_speed.max(0.0) + settings.lead_speed;
+            if walking_speed > allowed_speed {
+                player_velocity.linvel -= direction * (walking_speed - allowed_speed);
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+use bevy_fps_controller::controller::{fps_controller_render, FpsController, LogicalPlayer};
+use bevy_rapier3d::prelude::*;
+
+use super::movement_modes::MovementMode;
+use super::player_components::PlayerInput;
+use crate::MainCamera;
+
+const CROUCH_KEY: KeyCode = KeyCode::C;
+const PRONE_KEY: KeyCode = KeyCode::Z;
+const LEAN_LEFT_KEY: KeyCode = KeyCode::Q;
+const LEAN_RIGHT_KEY: KeyCode = KeyCode::E;
+/// Bottom of the player capsule segment, the feet stay here in every stance.
+const CAPSULE_BOTTOM: f32 = 0.5;
+const CAPSULE_RADIUS: f32 = 0.25;
+/// Radius of the probe that keeps the leaning camera out of walls.
+const LEAN_PROBE_RADIUS: f32 = 0.15;
+
+/// Crouching, going prone and leaning around corners.
+pub struct StancePlugin;
+
+impl Plugin for StancePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StanceSettings>()
+            .register_type::<StanceSettings>()
+            .register_type::<PlayerStance>()
+            .add_systems(Update, change_stance)
+            .add_systems(Update, stance_camera.after(fps_controller_render));
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Stance {
+    #[default]
+    Standing,
+    Crouching,
+    Prone,
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct StanceSettings {
+    /// Top of the capsule segment per stance.
+    pub standing_top: f32,
+    pub crouching_top: f32,
+    pub prone_top: f32,
+    /// Movement speed multipliers while crouching and prone.
+    pub crouching_speed: f32,
+    pub prone_speed: f32,
+    /// How fast the camera follows stance and lean changes, per second.
+    pub transition_speed: f32,
+    /// Sideways camera offset and roll at full lean.
+    pub lean_distance: f32,
+    pub lean_angle: f32,
+}
+
+impl Default for StanceSettings {
+    fn default() -> Self {
+        Self {
+            standing_top: 1.5,
+            crouching_top: 0.9,
+            prone_top: CAPSULE_BOTTOM,
+            crouching_speed: 0.5,
+            prone_speed: 0.25,
+            transition_speed: 10.0,
+            lean_distance: 0.5,
+            lean_angle: 0.25,
+        }
+    }
+}
+
+impl StanceSettings {
+    pub fn capsule_top(&self, stance: Stance) -> f32 {
+        match stance {
+            Stance::Standing => self.standing_top,
+            Stance::Crouching => self.crouching_top,
+            Stance::Prone => self.prone_top,
+        }
+    }
+
+    pub fn speed_multiplier(&self, stance: Stance) -> f32 {
+        match stance {
+            Stance::Standing => 1.0,
+            Stance::Crouching => self.crouching_speed,
+            Stance::Prone => self.prone_speed,
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug, Default)]
+pub struct PlayerStance {
+    pub stance: Stance,
+    /// Stance the player wants to be in, waits for headroom when standing up.
+    pub wanted: Stance,
+    /// Current lean from `-1` (left) to `1` (right).
+    pub lean: f32,
+    /// How far the camera is currently lowered below the standing eye height.
+    pub eye_drop: f32,
+    /// Walk and run speed of the controller while standing.
+    base_speeds: Option<(f32, f32)>,
+}
+
+fn change_stance(
+    key: Res<Input<KeyCode>>,
+    settings: Res<StanceSettings>,
+    rapier_context: Res<RapierContext>,
+    mut player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Collider,
+            &mut FpsController,
+            &mut PlayerStance,
+        ),
+        With<LogicalPlayer>,
+    >,
+    mut input_query: Query<&mut PlayerInput>,
+) {
+    for (entity, transform, mut collider, mut controller, mut player_stance) in
+        player_query.iter_mut()
+    {
+        if key.just_pressed(CROUCH_KEY) {
+            player_stance.wanted = match player_stance.wanted {
+                Stance::Crouching => Stance::Standing,
+                _ => Stance::Crouching,
+            };
+        }
+        if key.just_pressed(PRONE_KEY) {
+            player_stance.wanted = match player_stance.wanted {
+                Stance::Prone => Stance::Standing,
+                _ => Stance::Prone,
+            };
+        }
+        if player_stance.wanted == player_stance.stance {
+            continue;
+        }
+
+        let current_top = settings.capsule_top(player_stance.stance);
+        let wanted_top = settings.capsule_top(player_stance.wanted);
+        // Growing needs headroom, checked by sweeping the current capsule upwards.
+        if wanted_top > current_top {
+            let filter = QueryFilter::default()
+                .exclude_sensors()
+                .exclude_rigid_body(entity);
+            let blocked = rapier_context
+                .cast_shape(
+                    transform.translation,
+                    transform.rotation,
+                    Vec3::Y,
+                    &collider,
+                    wanted_top - current_top,
+                    filter,
+                )
+                .is_some();
+            if blocked {
+                continue;
+            }
+        }
+
+        player_stance.stance = player_stance.wanted;
+        *collider = Collider::capsule(
+            Vec3::Y * CAPSULE_BOTTOM,
+            Vec3::Y * wanted_top,
+            CAPSULE_RADIUS,
+        );
+        let (walk_speed, run_speed) = *player_stance
+            .base_speeds
+            .get_or_insert((controller.walk_speed, controller.run_speed));
+        let multiplier = settings.speed_multiplier(player_stance.stance);
+        controller.walk_speed = walk_speed * multiplier;
+        controller.run_speed = run_speed * multiplier;
+        for mut input in input_query.iter_mut() {
+            input.crouch = player_stance.stance != Stance::Standing;
+        }
+    }
+}
+
+/// Lowers and leans the camera after the controller placed it at standing eye height.
+pub fn stance_camera(
+    key: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    settings: Res<StanceSettings>,
+    rapier_context: Res<RapierContext>,
+    mut player_query: Query<(Entity, &mut PlayerStance, &MovementMode), With<LogicalPlayer>>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    let (Ok((player, mut player_stance, movement_mode)), Ok(mut camera)) =
+        (player_query.get_single_mut(), camera_query.get_single_mut())
+    else {
+        return;
+    };
+    let blend = (settings.transition_speed * time.delta_seconds()).min(1.0);
+
+    let target_drop = settings.standing_top - settings.capsule_top(player_stance.stance);
+    player_stance.eye_drop += (target_drop - player_stance.eye_drop) * blend;
+    camera.translation.y -= player_stance.eye_drop;
+
+    // Lean keys double as up and down while flying.
+    let mut target_lean = 0.0;
+    if player_stance.stance != Stance::Prone && *movement_mode == MovementMode::Walk {
+        if key.pressed(LEAN_LEFT_KEY) {
+            target_lean -= 1.0;
+        }
+        if key.pressed(LEAN_RIGHT_KEY) {
+            target_lean += 1.0;
+        }
+    }
+    player_stance.lean += (target_lean - player_stance.lean) * blend;
+    if player_stance.lean.abs() < 0.001 {
+        return;
+    }
+
+    // Only lean as far as the camera can go without entering geometry.
+    let side = camera.right() * player_stance.lean.signum();
+    let wanted_distance = player_stance.lean.abs() * settings.lean_distance;
+    let filter = QueryFilter::default()
+        .exclude_sensors()
+        .exclude_rigid_body(player);
+    let distance = rapier_context
+        .cast_shape(
+            camera.translation,
+            Quat::IDENTITY,
+            side,
+            &Collider::ball(LEAN_PROBE_RADIUS),
+            wanted_distance,
+            filter,
+        )
+        .map_or(wanted_distance, |(_, toi)| toi.toi);
+    camera.translation += side * distance;
+    let roll = -player_stance.lean * settings.lean_angle * distance / wanted_distance;
+    camera.rotate_local_z(roll);
+}
@@ -0,0 +1,180 @@
+pub mod palette;
+pub mod validation;
+
+use bevy::asset::HandleId;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use bevy_proto::backend::proto::Prototypical;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use self::palette::PalettePlugin;
+
+pub struct PrefabsPlugin;
+
+impl Plugin for PrefabsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PrototypeInstance>()
+            .init_resource::<PrototypeGraph>()
+            .add_plugins(PalettePlugin)
+            .add_systems(Update, track_prototype_graph)
+            .add_systems(
+                Update,
+                reload_modified_prototypes.after(track_prototype_graph),
+            );
+    }
+}
+
+/// Remembers which prototype a root entity was spawned from, so it can be hot-reloaded.
+#[derive(Component, Reflect, Default, Debug)]
+pub struct PrototypeInstance {
+    pub id: String,
+}
+
+/// Which prototypes use which other prototypes as children.
+#[derive(Resource, Default, Debug)]
+pub struct PrototypeGraph {
+    ids: HashMap<HandleId, String>,
+    /// Child prototypes per prototype id, resolved through `ids` as they finish loading.
+    children: HashMap<String, Vec<Handle<Prototype>>>,
+}
+
+impl PrototypeGraph {
+    /// Ids of all loaded prototypes.
+    pub fn ids(&self) -> impl Iterator<Item = &String> {
+        self.ids.values()
+    }
+
+    fn child_ids<'a>(&'a self, id: &str) -> impl Iterator<Item = &'a String> {
+        self.children
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|handle| self.ids.get(&handle.id()))
+    }
+
+    /// Whether `id` is used as a child of another prototype, and thus not a prefab of its own.
+    pub fn is_child(&self, id: &str) -> bool {
+        self.children
+            .keys()
+            .any(|parent| self.child_ids(parent).any(|child| child == id))
+    }
+
+    /// `id` and every prototype that contains it, directly or through other children.
+    pub fn dependents(&self, id: &str) -> HashSet<String> {
+        let mut dependents = HashSet::new();
+        let mut pending = vec![id.to_string()];
+        while let Some(current) = pending.pop() {
+            if !dependents.insert(current.clone()) {
+                continue;
+            }
+            for parent in self.children.keys() {
+                if self.child_ids(parent).any(|child| *child == current) {
+                    pending.push(parent.clone());
+                }
+            }
+        }
+        dependents
+    }
+}
+
+fn track_prototype_graph(
+    mut graph: ResMut<PrototypeGraph>,
+    prototype_assets: Res<Assets<Prototype>>,
+    mut proto_asset_events: EventReader<ProtoAssetEvent>,
+) {
+    for proto_asset_event in proto_asset_events.iter() {
+        match proto_asset_event {
+            ProtoAssetEvent::Created { id, handle } | ProtoAssetEvent::Modified { id, handle } => {
+                graph.ids.insert(handle.id(), id.clone());
+                let children = prototype_assets
+                    .get(handle)
+                    .and_then(|prototype| prototype.children())
+                    .map(|children| {
+                        children
+                            .iter()
+                            .map(|child| child.handle().clone_weak())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                graph.children.insert(id.clone(), children);
+            }
+            ProtoAssetEvent::Removed { id, handle } => {
+                graph.ids.remove(&handle.id());
+                graph.children.remove(id);
+            }
+        }
+    }
+}
+
+/// Spawns the prototype `id` at `transform` and tracks it for hot-reloading.
+pub fn spawn_prefab(commands: &mut ProtoCommands, id: &str, transform: Transform) -> Entity {
+    commands
+        .spawn(id)
+        .entity_commands()
+        .insert((transform, PrototypeInstance { id: id.to_string() }))
+        .id()
+}
+
+/// Re-applies a modified prototype to every instance that contains it in place.
+///
+/// The entity itself is kept, so joints referencing it (like a grab) stay intact,
+/// while its descendants are rebuilt from the prototype. Components the prototype no
+/// longer has are dropped, only the physical state is kept so the instance continues
+/// where it was.
+fn reload_modified_prototypes(
+    mut commands: ProtoCommands,
+    graph: Res<PrototypeGraph>,
+    mut proto_asset_events: EventReader<ProtoAssetEvent>,
+    instance_query: Query<(
+        Entity,
+        &PrototypeInstance,
+        &Transform,
+        Option<&Velocity>,
+        Option<&Sleeping>,
+    )>,
+) {
+    let mut modified = HashSet::new();
+    for proto_asset_event in proto_asset_events.iter() {
+        if let ProtoAssetEvent::Modified { id, .. } = proto_asset_event {
+            modified.extend(graph.dependents(id));
+        }
+    }
+    if modified.is_empty() {
+        return;
+    }
+
+    for (entity, instance, transform, velocity, sleeping) in instance_query.iter() {
+        if !modified.contains(&instance.id) {
+            continue;
+        }
+        info!("reloading {:?} from prototype {}", entity, instance.id);
+
+        // The body and its handle are kept, re-creating it would break its joints. The
+        // collider is rebuilt from the reloaded prototype so shape edits take effect.
+        commands
+            .entity(entity)
+            .entity_commands()
+            .despawn_descendants()
+            .retain::<(
+                PrototypeInstance,
+                Transform,
+                GlobalTransform,
+                Parent,
+                Velocity,
+                Sleeping,
+                RigidBody,
+                RapierRigidBodyHandle,
+            )>();
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(instance.id.as_str());
+        let mut entity_commands = entity_commands.entity_commands();
+        entity_commands.insert(*transform);
+        if let Some(velocity) = velocity {
+            entity_commands.insert(*velocity);
+        }
+        if let Some(sleeping) = sleeping {
+            entity_commands.insert(*sleeping);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_fps_controller::controller::LogicalPlayer;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::f32::consts::TAU;
+
+use super::{spawn_prefab, track_prototype_graph, PrototypeGraph, PrototypeInstance};
+use crate::MainCamera;
+
+const PALETTE_TOGGLE_KEY: KeyCode = KeyCode::B;
+const PALETTE_PREVIOUS_KEY: KeyCode = KeyCode::Comma;
+const PALETTE_NEXT_KEY: KeyCode = KeyCode::Period;
+const PALETTE_ROTATE_KEY: KeyCode = KeyCode::R;
+const PALETTE_SPAWN_KEY: KeyCode = KeyCode::Return;
+const ROTATION_STEP: f32 = TAU / 8.0;
+const MAX_PLACEMENT_DISTANCE: f32 = 30.0;
+/// Half extents of the ghost for prototypes that were never spawned yet.
+const UNKNOWN_HALF_EXTENTS: Vec3 = Vec3::splat(0.25);
+
+/// In-game tool to place any loaded prototype at the crosshair.
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PrefabLibrary>()
+            .init_resource::<SpawnPalette>()
+            .add_systems(Startup, setup_palette_text)
+            .add_systems(Update, track_loaded_prototypes.after(track_prototype_graph))
+            .add_systems(Update, measure_prefab_bounds)
+            .add_systems(Update, palette_input.after(track_loaded_prototypes))
+            .add_systems(Update, palette_placement.after(palette_input))
+            .add_systems(Update, update_palette_text.after(palette_input));
+    }
+}
+
+/// Ids of all currently loaded prototypes that can be spawned on their own, sorted by name.
+#[derive(Resource, Default, Debug)]
+pub struct PrefabLibrary {
+    pub ids: Vec<String>,
+    /// Collider bounds per prototype, measured on spawned instances.
+    pub bounds: HashMap<String, PrefabBounds>,
+}
+
+/// Box around all colliders of a prefab, in the root's space.
+#[derive(Debug, Clone, Copy)]
+pub struct PrefabBounds {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct SpawnPalette {
+    pub active: bool,
+    pub selected: usize,
+    /// Rotation around the up axis applied to spawned prototypes.
+    pub rotation: f32,
+}
+
+impl SpawnPalette {
+    pub fn selected_id<'a>(&self, library: &'a PrefabLibrary) -> Option<&'a String> {
+        library.ids.get(self.selected)
+    }
+}
+
+#[derive(Component)]
+struct PaletteText;
+
+/// Lists the root prototypes, children only exist as part of their parents.
+fn track_loaded_prototypes(mut library: ResMut<PrefabLibrary>, graph: Res<PrototypeGraph>) {
+    if !graph.is_changed() {
+        return;
+    }
+    let mut ids: Vec<String> = graph
+        .ids()
+        .filter(|id| !graph.is_child(id))
+        .cloned()
+        .collect();
+    ids.sort();
+    if library.ids != ids {
+        library.ids = ids;
+    }
+}
+
+/// Measures the colliders of spawned instances to size the placement ghost.
+///
+/// Goes through the local transforms, global ones of freshly spawned children are
+/// only propagated at the end of the frame.
+fn measure_prefab_bounds(
+    mut library: ResMut<PrefabLibrary>,
+    changed_query: Query<(), Changed<Collider>>,
+    instance_query: Query<(Entity, &PrototypeInstance)>,
+    children_query: Query<&Children>,
+    transform_query: Query<&Transform>,
+    collider_query: Query<&Collider>,
+) {
+    if changed_query.is_empty() {
+        return;
+    }
+    for (root, instance) in instance_query.iter() {
+        let mut min = Vec3::splat(f32::MAX);
+        let mut max = Vec3::splat(f32::MIN);
+        let mut pending = vec![(root, Affine3A::IDENTITY)];
+        while let Some((entity, to_root)) = pending.pop() {
+            if let Ok(children) = children_query.get(entity) {
+                for child in children.iter() {
+                    let local = transform_query
+                        .get(*child)
+                        .map_or(Affine3A::IDENTITY, |transform| transform.compute_affine());
+                    pending.push((*child, to_root * local));
+                }
+            }
+            let Ok(collider) = collider_query.get(entity) else {
+                continue;
+            };
+            let aabb = collider.raw.compute_local_aabb();
+            for corner in aabb.vertices() {
+                let corner = to_root.transform_point3(Vec3::new(corner.x, corner.y, corner.z));
+                min = min.min(corner);
+                max = max.max(corner);
+            }
+        }
+        if min.cmple(max).all() {
+            library.bounds.insert(
+                instance.id.clone(),
+                PrefabBounds {
+                    center: (min + max) * 0.5,
+                    half_extents: (max - min) * 0.5,
+                },
+            );
+        }
+    }
+}
+
+fn palette_input(
+    key: Res<Input<KeyCode>>,
+    library: Res<PrefabLibrary>,
+    mut palette: ResMut<SpawnPalette>,
+) {
+    if key.just_pressed(PALETTE_TOGGLE_KEY) {
+        palette.active = !palette.active;
+    }
+    if !palette.active || library.ids.is_empty() {
+        return;
+    }
+    let count = library.ids.len();
+    if key.just_pressed(PALETTE_NEXT_KEY) {
+        palette.selected = (palette.selected + 1) % count;
+    }
+    if key.just_pressed(PALETTE_PREVIOUS_KEY) {
+        palette.selected = (palette.selected + count - 1) % count;
+    }
+    palette.selected = palette.selected.min(count - 1);
+    if key.just_pressed(PALETTE_ROTATE_KEY) {
+        palette.rotation = (palette.rotation + ROTATION_STEP) % TAU;
+    }
+}
+
+fn palette_placement(
+    mut commands: ProtoCommands,
+    key: Res<Input<KeyCode>>,
+    palette: Res<SpawnPalette>,
+    library: Res<PrefabLibrary>,
+    rapier_context: Res<RapierContext>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+    player_query: Query<Entity, With<LogicalPlayer>>,
+    mut gizmos: Gizmos,
+) {
+    if !palette.active {
+        return;
+    }
+    let Some(id) = palette.selected_id(&library) else {
+        return;
+    };
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+
+    let mut filter = QueryFilter::default().exclude_sensors();
+    if let Ok(player) = player_query.get_single() {
+        filter = filter.exclude_rigid_body(player);
+    }
+    let Some((_, hit)) = rapier_context.cast_ray_and_get_normal(
+        camera.translation,
+        camera.forward(),
+        MAX_PLACEMENT_DISTANCE,
+        true,
+        filter,
+    ) else {
+        return;
+    };
+
+    let rotation = Quat::from_rotation_y(palette.rotation);
+    // Spawn slightly above the surface and let the prop settle on it.
+    let position = hit.point + hit.normal * 0.3;
+    let ghost_color = Color::rgba(0.4, 0.8, 1.0, 0.6);
+    let bounds = library.bounds.get(id).copied().unwrap_or(PrefabBounds {
+        center: Vec3::ZERO,
+        half_extents: UNKNOWN_HALF_EXTENTS,
+    });
+    gizmos.cuboid(
+        Transform::from_translation(position + rotation * bounds.center)
+            .with_rotation(rotation)
+            .with_scale(bounds.half_extents * 2.0),
+        ghost_color,
+    );
+    gizmos.ray(position, rotation * Vec3::NEG_Z * 0.5, ghost_color);
+    gizmos.circle(hit.point, hit.normal, 0.1, ghost_color);
+
+    if key.just_pressed(PALETTE_SPAWN_KEY) {
+        spawn_prefab(
+            &mut commands,
+            id,
+            Transform::from_translation(position).with_rotation(rotation),
+        );
+    }
+}
+
+fn setup_palette_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 18.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        PaletteText,
+        Name::new("palette_text"),
+    ));
+}
+
+fn update_palette_text(
+    palette: Res<SpawnPalette>,
+    library: Res<PrefabLibrary>,
+    mut text_query: Query<&mut Text, With<PaletteText>>,
+) {
+    if !palette.is_changed() && !library.is_changed() {
+        return;
+    }
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    text.sections[0].value = match (palette.active, palette.selected_id(&library)) {
+        (false, _) => String::new(),
+        (true, None) => "palette: no prototypes loaded".to_string(),
+        (true, Some(id)) => format!(
+            "palette: {} ({}/{})  rotation {:.0}°\n[,/.] select  [R] rotate  [Enter] spawn  [B] close",
+            id,
+            palette.selected + 1,
+            library.ids.len(),
+            palette.rotation.to_degrees()
+        ),
+    };
+}
@@ -0,0 +1,441 @@
+use bevy::asset::{Asset, LoadState};
+use bevy::prelude::*;
+use bevy::render::settings::WgpuSettings;
+use bevy::render::RenderPlugin;
+use bevy::utils::{HashMap, HashSet};
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+use bevy_proto::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::time::{Duration, Instant};
+
+use crate::components::Weapon;
+use crate::PrototypeSchematicsPlugin;
+
+/// Command line flag that runs the prefab validation instead of the game.
+pub const VALIDATE_PREFABS_ARG: &str = "--validate-prefabs";
+
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const PHYSICS_TICKS: usize = 10;
+const SPAWN_SPACING: f32 = 10.0;
+
+/// Loads every prototype in `assets/prefabs` headlessly, spawns each one, steps physics
+/// a few ticks and prints everything that looks broken.
+///
+/// Returns the process exit code: `0` if all prefabs are fine, `1` otherwise.
+pub fn run() -> i32 {
+    if validate("assets").is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+/// Something that looks broken about a prototype.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    /// Id of the prototype, or the path of a file that could not be loaded.
+    pub prototype: String,
+    pub message: String,
+}
+
+/// Validates the prototypes in the `prefabs` folder of `asset_folder` and returns every
+/// problem found.
+fn validate(asset_folder: &str) -> Vec<Problem> {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .build()
+            .disable::<WinitPlugin>()
+            .set(AssetPlugin {
+                asset_folder: asset_folder.to_string(),
+                ..default()
+            })
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                wgpu_settings: WgpuSettings {
+                    backends: None,
+                    ..default()
+                },
+            }),
+    )
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(ProtoPlugin::default())
+    .add_plugins(PrototypeSchematicsPlugin)
+    .init_resource::<Validation>()
+    .add_systems(Startup, load_prototypes)
+    .add_systems(Update, collect_prototype_ids)
+    .add_systems(Update, spawn_ready_prototypes.after(collect_prototype_ids))
+    .add_systems(Update, read_dynamic_body_mass);
+
+    let loaded = update_until(&mut app, |world| {
+        let validation = world.resource::<Validation>();
+        let asset_server = world.resource::<AssetServer>();
+        !validation.handles.is_empty()
+            && validation.handles.iter().all(|handle| {
+                matches!(
+                    asset_server.get_load_state(handle),
+                    LoadState::Loaded | LoadState::Failed
+                )
+            })
+    });
+    if !loaded {
+        report_pending_loads(&mut app.world);
+    }
+
+    let spawned = update_until(&mut app, |world| {
+        let validation = world.resource::<Validation>();
+        validation
+            .ids
+            .values()
+            .all(|id| validation.spawned.contains_key(id))
+    });
+    if !spawned {
+        let validation = app.world.resource::<Validation>();
+        let missing: Vec<String> = validation
+            .ids
+            .values()
+            .filter(|id| !validation.spawned.contains_key(*id))
+            .cloned()
+            .collect();
+        for id in missing {
+            fail(
+                &mut app.world,
+                &id,
+                "never became ready, one of its dependencies failed to load",
+            );
+        }
+    }
+
+    // Give scenes and other dependencies time to load, then step physics.
+    update_until(&mut app, |world| !scenes_loading(world));
+    for _ in 0..PHYSICS_TICKS {
+        std::thread::sleep(Duration::from_millis(16));
+        app.update();
+    }
+
+    check_spawned_prototypes(&mut app.world);
+
+    let mut validation = app.world.resource_mut::<Validation>();
+    println!(
+        "validated {} prototypes, {} problems found",
+        validation.ids.len(),
+        validation.problems.len()
+    );
+    std::mem::take(&mut validation.problems)
+}
+
+#[derive(Resource, Default)]
+struct Validation {
+    handles: Vec<Handle<Prototype>>,
+    ids: HashMap<Handle<Prototype>, String>,
+    spawned: HashMap<String, Entity>,
+    problems: Vec<Problem>,
+}
+
+fn fail(world: &mut World, prototype: &str, message: &str) {
+    println!("[{}] {}", prototype, message);
+    world.resource_mut::<Validation>().problems.push(Problem {
+        prototype: prototype.to_string(),
+        message: message.to_string(),
+    });
+}
+
+/// Runs frames until `done` holds or loading times out, returns whether it finished.
+fn update_until(app: &mut App, done: impl Fn(&World) -> bool) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < LOAD_TIMEOUT {
+        app.update();
+        if done(&app.world) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+fn load_prototypes(mut prototypes: PrototypesMut, mut validation: ResMut<Validation>) {
+    validation.handles = prototypes.load_folder("prefabs").unwrap();
+}
+
+fn collect_prototype_ids(
+    mut proto_asset_events: EventReader<ProtoAssetEvent>,
+    mut validation: ResMut<Validation>,
+) {
+    for proto_asset_event in proto_asset_events.iter() {
+        if let ProtoAssetEvent::Created { id, handle } = proto_asset_event {
+            validation.ids.insert(handle.clone_weak(), id.clone());
+        }
+    }
+}
+
+fn spawn_ready_prototypes(
+    mut commands: ProtoCommands,
+    prototypes: Prototypes,
+    mut validation: ResMut<Validation>,
+) {
+    let ready: Vec<String> = validation
+        .ids
+        .values()
+        .filter(|id| !validation.spawned.contains_key(*id) && prototypes.is_ready(id.as_str()))
+        .cloned()
+        .collect();
+    for id in ready {
+        let offset = validation.spawned.len() as f32 * SPAWN_SPACING;
+        let entity = commands
+            .spawn(id.as_str())
+            .entity_commands()
+            .insert(Transform::from_xyz(offset, 0.0, 0.0))
+            .id();
+        validation.spawned.insert(id, entity);
+    }
+}
+
+fn read_dynamic_body_mass(
+    mut commands: Commands,
+    body_query: Query<(Entity, &RigidBody), Without<ReadMassProperties>>,
+) {
+    for (entity, rigid_body) in body_query.iter() {
+        if *rigid_body == RigidBody::Dynamic {
+            commands
+                .entity(entity)
+                .insert(ReadMassProperties::default());
+        }
+    }
+}
+
+fn report_pending_loads(world: &mut World) {
+    let asset_server = world.resource::<AssetServer>();
+    let problems: Vec<(String, LoadState)> = world
+        .resource::<Validation>()
+        .handles
+        .iter()
+        .map(|handle| {
+            (
+                asset_path(asset_server, handle),
+                asset_server.get_load_state(handle),
+            )
+        })
+        .filter(|(_, state)| !matches!(state, LoadState::Loaded | LoadState::Failed))
+        .collect();
+    for (path, state) in problems {
+        fail(
+            world,
+            &path,
+            &format!("did not finish loading ({:?})", state),
+        );
+    }
+}
+
+fn scenes_loading(world: &World) -> bool {
+    let asset_server = world.resource::<AssetServer>();
+    world
+        .iter_entities()
+        .filter_map(|entity| entity.get::<Handle<Scene>>())
+        .any(|handle| asset_server.get_load_state(handle) == LoadState::Loading)
+}
+
+fn check_spawned_prototypes(world: &mut World) {
+    let mut problems = Vec::new();
+
+    // Prototype files that failed to parse, e.g. because of an unknown schematic type.
+    {
+        let validation = world.resource::<Validation>();
+        let asset_server = world.resource::<AssetServer>();
+        for handle in validation.handles.iter() {
+            if asset_server.get_load_state(handle) == LoadState::Failed {
+                problems.push((
+                    asset_path(asset_server, handle),
+                    "failed to load, check for unknown schematic types or syntax errors"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+
+    let known_ids: HashSet<String> = world
+        .resource::<Validation>()
+        .ids
+        .values()
+        .cloned()
+        .collect();
+    let spawned: Vec<(String, Entity)> = world
+        .resource::<Validation>()
+        .spawned
+        .iter()
+        .map(|(id, entity)| (id.clone(), *entity))
+        .collect();
+
+    for (id, root) in spawned {
+        let entities = descendants(world, root);
+        let describe = |entity: Entity| {
+            world
+                .get::<Name>(entity)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("{:?}", entity))
+        };
+
+        let asset_server = world.resource::<AssetServer>();
+        for entity in entities.iter() {
+            let Some(scene) = world.get::<Handle<Scene>>(*entity) else {
+                continue;
+            };
+            if asset_server.get_load_state(scene) == LoadState::Failed {
+                problems.push((
+                    id.clone(),
+                    format!("missing asset {}", asset_path(asset_server, scene)),
+                ));
+            }
+        }
+
+        // Prototypes referenced by id from schematics.
+        for entity in entities.iter() {
+            let Some(weapon) = world.get::<Weapon>(*entity) else {
+                continue;
+            };
+            if !known_ids.contains(&weapon.projectile) {
+                problems.push((
+                    id.clone(),
+                    format!(
+                        "weapon {} fires unknown prototype {:?}",
+                        describe(*entity),
+                        weapon.projectile
+                    ),
+                ));
+            }
+        }
+
+        // Colliders belong to the body they are attached to, directly or through a parent.
+        let rapier_context = world.resource::<RapierContext>();
+        for entity in entities.iter() {
+            if world.get::<RigidBody>(*entity) != Some(&RigidBody::Dynamic) {
+                continue;
+            }
+            let has_collider = entities.iter().any(|collider| {
+                world.get::<Collider>(*collider).is_some()
+                    && rapier_context.collider_parent(*collider) == Some(*entity)
+            });
+            let mass = world.get::<ReadMassProperties>(*entity);
+            if !has_collider {
+                problems.push((
+                    id.clone(),
+                    format!("dynamic body {} has no collider", describe(*entity)),
+                ));
+            } else if mass.map_or(true, |mass| mass.get().mass <= 0.0) {
+                problems.push((
+                    id.clone(),
+                    format!("dynamic body {} has zero mass", describe(*entity)),
+                ));
+            }
+        }
+
+        // Jointed bodies are meant to touch at their anchors.
+        let jointed: HashSet<(Entity, Entity)> = entities
+            .iter()
+            .filter_map(|entity| {
+                let joint = world.get::<ImpulseJoint>(*entity)?;
+                Some(((*entity).min(joint.parent), (*entity).max(joint.parent)))
+            })
+            .collect();
+        let mut overlapping = HashSet::new();
+        for entity in entities.iter() {
+            let (Some(collider), Some(transform)) = (
+                world.get::<Collider>(*entity),
+                world.get::<GlobalTransform>(*entity),
+            ) else {
+                continue;
+            };
+            let Some(body) = rapier_context.collider_parent(*entity) else {
+                continue;
+            };
+            let (_, rotation, translation) = transform.to_scale_rotation_translation();
+            rapier_context.intersections_with_shape(
+                translation,
+                rotation,
+                collider,
+                QueryFilter::default()
+                    .exclude_collider(*entity)
+                    .exclude_rigid_body(body),
+                |other| {
+                    let Some(other_body) = rapier_context.collider_parent(other) else {
+                        return true;
+                    };
+                    let pair = (body.min(other_body), body.max(other_body));
+                    if entities.contains(&other) && !jointed.contains(&pair) {
+                        overlapping.insert(((*entity).min(other), (*entity).max(other)));
+                    }
+                    true
+                },
+            );
+        }
+        for (first, second) in overlapping {
+            problems.push((
+                id.clone(),
+                format!(
+                    "colliders {} and {} of different bodies interpenetrate",
+                    describe(first),
+                    describe(second)
+                ),
+            ));
+        }
+    }
+
+    for (prototype, message) in problems {
+        fail(world, &prototype, &message);
+    }
+}
+
+fn asset_path<T: Asset>(asset_server: &AssetServer, handle: &Handle<T>) -> String {
+    asset_server
+        .get_handle_path(handle)
+        .map(|path| path.path().display().to_string())
+        .unwrap_or_else(|| format!("{:?}", handle))
+}
+
+fn descendants(world: &World, root: Entity) -> Vec<Entity> {
+    let mut entities = vec![root];
+    let mut index = 0;
+    while index < entities.len() {
+        if let Some(children) = world.get::<Children>(entities[index]) {
+            entities.extend(children.iter().copied());
+        }
+        index += 1;
+    }
+    entities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reports(problems: &[Problem], prototype: &str, message: &str) -> bool {
+        problems
+            .iter()
+            .any(|problem| problem.prototype == prototype && problem.message.contains(message))
+    }
+
+    #[test]
+    fn broken_prefabs_fail_validation() {
+        let problems = validate("tests/fixtures/broken_assets");
+        assert!(
+            reports(&problems, "colliderless_body", "has no collider"),
+            "{:?}",
+            problems
+        );
+        assert!(
+            reports(&problems, "overlapping_bodies", "interpenetrate"),
+            "{:?}",
+            problems
+        );
+    }
+
+    #[test]
+    fn shipped_prefabs_pass_validation() {
+        let problems = validate("assets");
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+}